@@ -3,7 +3,7 @@
 //! Note: Twitch requires you to set `client_id` and `client_secret` as extra
 //! parameters when performing the token exchange (see below).
 
-use oauth2::{AccessToken, Client, RefreshToken, Scope, State, Token, TokenType, Url};
+use oauth2::{AccessToken, Client, RefreshToken, Scopes, State, Token, TokenType, Url};
 use oauth2_examples::{config_from_args, listen_for_code};
 use std::time::Duration;
 
@@ -18,7 +18,7 @@ pub struct TwitchToken {
     #[serde(rename = "scope")]
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
-    scopes: Option<Vec<Scope>>,
+    scopes: Option<Scopes>,
 }
 
 impl Token for TwitchToken {
@@ -38,7 +38,7 @@ impl Token for TwitchToken {
         self.refresh_token.as_ref()
     }
 
-    fn scopes(&self) -> Option<&Vec<Scope>> {
+    fn scopes(&self) -> Option<&Scopes> {
         self.scopes.as_ref()
     }
 }
@@ -0,0 +1,397 @@
+//! Presenting access tokens to protected resources, as described in
+//! [RFC 6750](https://tools.ietf.org/html/rfc6750).
+
+use core::error::Error;
+use core::fmt;
+use core::mem;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use http::header::{HeaderValue, AUTHORIZATION, CACHE_CONTROL, CONTENT_TYPE};
+use http::uri::{PathAndQuery, Uri};
+use http::Method;
+
+use crate::{AccessToken, StrictViolation, Token, TokenType};
+
+const CONTENT_TYPE_FORM: &str = "application/x-www-form-urlencoded";
+
+/// Attaches an access token to outgoing requests to a protected resource, as
+/// described in [RFC 6750](https://tools.ietf.org/html/rfc6750).
+///
+/// The token can be presented in one of three ways:
+/// * [`Bearer::apply_header`] uses the `Authorization` header, which is the
+///   recommended method.
+/// * [`Bearer::apply_form`] adds an `access_token` parameter to a form
+///   encoded body.
+/// * [`Bearer::apply_query`] adds an `access_token` query parameter, which
+///   has to be explicitly allowed through [`Bearer::allow_query`].
+///
+/// The `Authorization` header is marked as [sensitive], so that it is
+/// redacted from the debug output of the request, and the access token is
+/// redacted from the debug output of this type.
+///
+/// # Examples
+///
+/// ```
+/// use oauth2::{Bearer, StandardToken};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let token = serde_json::from_str::<StandardToken>(
+///     r#"{"access_token":"mF_9.B5f-4.1JqM","token_type":"bearer"}"#,
+/// )?;
+///
+/// let mut request = http::Request::get("https://api.example.com/me").body(())?;
+/// Bearer::new(&token).apply_header(&mut request)?;
+///
+/// assert_eq!(request.headers()["authorization"], "Bearer mF_9.B5f-4.1JqM");
+/// assert!(!format!("{request:?}").contains("mF_9.B5f-4.1JqM"));
+/// # Ok(()) }
+/// ```
+///
+/// [sensitive]: http::HeaderValue::set_sensitive
+#[derive(Clone)]
+pub struct Bearer<'a> {
+    access_token: &'a AccessToken,
+    token_type: &'a TokenType,
+    strict: bool,
+    allow_query: bool,
+}
+
+impl<'a> Bearer<'a> {
+    /// Construct a new helper for the given token.
+    ///
+    /// Use [`Client::bearer`] instead to take [strict mode] into account.
+    ///
+    /// [`Client::bearer`]: crate::Client::bearer
+    /// [strict mode]: crate::Client::set_strict
+    pub fn new<T>(token: &'a T) -> Self
+    where
+        T: Token,
+    {
+        Self {
+            access_token: token.access_token(),
+            token_type: token.token_type(),
+            strict: false,
+            allow_query: false,
+        }
+    }
+
+    /// Set whether the helper operates in [strict mode], in which case
+    /// [`Bearer::apply_query`] is refused even if it has been allowed.
+    ///
+    /// [strict mode]: crate::Client::set_strict
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Allow the access token to be sent as a query parameter through
+    /// [`Bearer::apply_query`].
+    ///
+    /// [Section 5.3 of RFC 6750](https://tools.ietf.org/html/rfc6750#section-5.3)
+    /// recommends against this, since URLs are likely to end up in logs and
+    /// browser history. Only allow it for resource servers which support no
+    /// other method.
+    pub fn allow_query(mut self) -> Self {
+        self.allow_query = true;
+        self
+    }
+
+    /// Construct the value of an `Authorization` header for the token.
+    ///
+    /// Only tokens of type [`TokenType::Bearer`] can be presented through a
+    /// header alone, so this errors for all other token types. In particular
+    /// a [`TokenType::DPoP`] token must be accompanied by a proof of
+    /// possession in the `DPoP` header, and a [`TokenType::Mac`] token by a
+    /// signature of the request, neither of which is supported.
+    pub fn header_value(&self) -> Result<HeaderValue, BearerError> {
+        match self.token_type {
+            TokenType::Bearer => {}
+            TokenType::NA => return Err(BearerError::from(BearerErrorKind::NotAccessToken)),
+            _ => return Err(BearerError::from(BearerErrorKind::UnsupportedTokenType)),
+        }
+
+        let mut value = String::from("Bearer ");
+        value.push_str(self.access_token);
+
+        let Ok(mut value) = HeaderValue::try_from(value) else {
+            return Err(BearerError::from(BearerErrorKind::InvalidAccessToken));
+        };
+
+        value.set_sensitive(true);
+        Ok(value)
+    }
+
+    /// Present the token in the `Authorization` header of the request,
+    /// replacing any existing value.
+    ///
+    /// See [Section 2.1 of RFC 6750](https://tools.ietf.org/html/rfc6750#section-2.1).
+    pub fn apply_header<B>(&self, request: &mut http::Request<B>) -> Result<(), BearerError> {
+        let value = self.header_value()?;
+        request.headers_mut().insert(AUTHORIZATION, value);
+        Ok(())
+    }
+
+    /// Present the token as an `access_token` parameter appended to the form
+    /// encoded body of the request.
+    ///
+    /// As required by
+    /// [Section 2.2 of RFC 6750](https://tools.ietf.org/html/rfc6750#section-2.2),
+    /// this is only permitted for bearer tokens, and for requests which use a
+    /// method other than `GET` or `HEAD` with a form encoded body. A missing
+    /// `Content-Type` header is set accordingly.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use oauth2::{Bearer, StandardToken};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let token = serde_json::from_str::<StandardToken>(
+    ///     r#"{"access_token":"mF_9.B5f-4.1JqM","token_type":"bearer"}"#,
+    /// )?;
+    ///
+    /// let mut request = http::Request::post("https://api.example.com/resource")
+    ///     .body(b"name=value".to_vec())?;
+    ///
+    /// Bearer::new(&token).apply_form(&mut request)?;
+    /// assert_eq!(request.body(), b"name=value&access_token=mF_9.B5f-4.1JqM");
+    /// # Ok(()) }
+    /// ```
+    pub fn apply_form<B>(&self, request: &mut http::Request<B>) -> Result<(), BearerError>
+    where
+        B: Default + From<Vec<u8>> + Into<Vec<u8>>,
+    {
+        self.check_bearer()?;
+
+        if request.method() == Method::GET || request.method() == Method::HEAD {
+            return Err(BearerError::from(BearerErrorKind::FormMethod));
+        }
+
+        match request.headers().get(CONTENT_TYPE) {
+            Some(content_type) => {
+                let media_type = content_type
+                    .to_str()
+                    .ok()
+                    .and_then(|value| value.split(';').next())
+                    .map(str::trim);
+
+                if !media_type.is_some_and(|value| value.eq_ignore_ascii_case(CONTENT_TYPE_FORM)) {
+                    return Err(BearerError::from(BearerErrorKind::FormContentType));
+                }
+            }
+            None => {
+                request
+                    .headers_mut()
+                    .insert(CONTENT_TYPE, HeaderValue::from_static(CONTENT_TYPE_FORM));
+            }
+        }
+
+        let mut body: Vec<u8> = mem::take(request.body_mut()).into();
+
+        let param = url::form_urlencoded::Serializer::new(String::new())
+            .append_pair("access_token", self.access_token)
+            .finish();
+
+        if !body.is_empty() {
+            body.push(b'&');
+        }
+
+        body.extend_from_slice(param.as_bytes());
+        *request.body_mut() = B::from(body);
+        Ok(())
+    }
+
+    /// Present the token as an `access_token` query parameter.
+    ///
+    /// This is only permitted for bearer tokens, and has to be explicitly
+    /// allowed through [`Bearer::allow_query`]. In strict mode it is refused
+    /// with [`StrictViolation::BearerTokenInQuery`].
+    ///
+    /// As recommended by
+    /// [Section 2.3 of RFC 6750](https://tools.ietf.org/html/rfc6750#section-2.3),
+    /// a `Cache-Control: no-store` header is added to the request. Note that
+    /// the access token will be visible in the URI of the request, which
+    /// can't be redacted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use oauth2::{Bearer, StandardToken};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let token = serde_json::from_str::<StandardToken>(
+    ///     r#"{"access_token":"mF_9.B5f-4.1JqM","token_type":"bearer"}"#,
+    /// )?;
+    ///
+    /// let mut request = http::Request::get("https://api.example.com/resource?a=b").body(())?;
+    ///
+    /// assert!(Bearer::new(&token).apply_query(&mut request).is_err());
+    ///
+    /// Bearer::new(&token).allow_query().apply_query(&mut request)?;
+    /// assert_eq!(request.uri(), "https://api.example.com/resource?a=b&access_token=mF_9.B5f-4.1JqM");
+    /// # Ok(()) }
+    /// ```
+    pub fn apply_query<B>(&self, request: &mut http::Request<B>) -> Result<(), BearerError> {
+        if self.strict {
+            return Err(BearerError::from(BearerErrorKind::Strict {
+                violation: StrictViolation::BearerTokenInQuery,
+            }));
+        }
+
+        if !self.allow_query {
+            return Err(BearerError::from(BearerErrorKind::QueryNotAllowed));
+        }
+
+        self.check_bearer()?;
+
+        let mut parts = request.uri().clone().into_parts();
+
+        let (path, query) = match &parts.path_and_query {
+            Some(path_and_query) => (path_and_query.path(), path_and_query.query()),
+            None => ("/", None),
+        };
+
+        let mut path_and_query = String::from(path);
+        path_and_query.push('?');
+
+        if let Some(query) = query {
+            path_and_query.push_str(query);
+        }
+
+        url::form_urlencoded::Serializer::for_suffix(&mut path_and_query, path.len() + 1)
+            .append_pair("access_token", self.access_token);
+
+        let Ok(path_and_query) = PathAndQuery::try_from(path_and_query) else {
+            return Err(BearerError::from(BearerErrorKind::InvalidAccessToken));
+        };
+
+        parts.path_and_query = Some(path_and_query);
+
+        let Ok(uri) = Uri::from_parts(parts) else {
+            return Err(BearerError::from(BearerErrorKind::InvalidAccessToken));
+        };
+
+        *request.uri_mut() = uri;
+
+        request
+            .headers_mut()
+            .insert(CACHE_CONTROL, HeaderValue::from_static("no-store"));
+
+        Ok(())
+    }
+
+    /// Test that the token can be sent outside of the `Authorization`
+    /// header, which is only defined for bearer tokens.
+    fn check_bearer(&self) -> Result<(), BearerError> {
+        if *self.token_type != TokenType::Bearer {
+            return Err(BearerError::from(BearerErrorKind::NotBearer));
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Bearer<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Bearer")
+            .field("access_token", &self.access_token)
+            .field("token_type", &self.token_type)
+            .field("strict", &self.strict)
+            .field("allow_query", &self.allow_query)
+            .finish()
+    }
+}
+
+/// Error encountered while attaching an access token to a request through
+/// [`Bearer`].
+pub struct BearerError {
+    kind: BearerErrorKind,
+}
+
+impl BearerError {
+    /// The strict mode violation which caused the token to be refused, if
+    /// any.
+    pub fn strict_violation(&self) -> Option<StrictViolation> {
+        match self.kind {
+            BearerErrorKind::Strict { violation } => Some(violation),
+            _ => None,
+        }
+    }
+}
+
+impl From<BearerErrorKind> for BearerError {
+    #[inline]
+    fn from(kind: BearerErrorKind) -> Self {
+        Self { kind }
+    }
+}
+
+impl Error for BearerError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self.kind {
+            BearerErrorKind::Strict { ref violation } => Some(violation),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for BearerError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.kind.fmt(f)
+    }
+}
+
+impl fmt::Debug for BearerError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.kind.fmt(f)
+    }
+}
+
+#[derive(Debug)]
+enum BearerErrorKind {
+    /// The token is not an access token.
+    NotAccessToken,
+    /// The token type requires more than an `Authorization` header.
+    UnsupportedTokenType,
+    /// The token is not a bearer token.
+    NotBearer,
+    /// The access token contains characters which can't be sent.
+    InvalidAccessToken,
+    /// The request method doesn't permit a form encoded body.
+    FormMethod,
+    /// The request body is not form encoded.
+    FormContentType,
+    /// Query parameters have not been allowed.
+    QueryNotAllowed,
+    /// The request was refused since it violates strict mode.
+    Strict {
+        /// The violation that was detected.
+        violation: StrictViolation,
+    },
+}
+
+impl fmt::Display for BearerErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            BearerErrorKind::NotAccessToken => "token can't be used as an access token".fmt(f),
+            BearerErrorKind::UnsupportedTokenType => {
+                "token type can't be presented through an authorization header".fmt(f)
+            }
+            BearerErrorKind::NotBearer => {
+                "only bearer tokens can be sent outside of the authorization header".fmt(f)
+            }
+            BearerErrorKind::InvalidAccessToken => {
+                "access token contains invalid characters".fmt(f)
+            }
+            BearerErrorKind::FormMethod => "request method doesn't permit a form body".fmt(f),
+            BearerErrorKind::FormContentType => "request body is not form encoded".fmt(f),
+            BearerErrorKind::QueryNotAllowed => "access token in query has not been allowed".fmt(f),
+            BearerErrorKind::Strict { .. } => "request violates strict mode".fmt(f),
+        }
+    }
+}
@@ -209,6 +209,56 @@
 //!
 //! <br>
 //!
+//! ## Client-Initiated Backchannel Authentication
+//!
+//! The [CIBA] flow authenticates a user on a separate device, such as their
+//! phone, without any browser redirects. The client first sends a
+//! backchannel authentication request identifying the user through a hint,
+//! and then polls the token endpoint until the user has approved or denied
+//! the request.
+//!
+//! ```no_run
+//! use oauth2::*;
+//! use url::Url;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let reqwest_client = reqwest::Client::new();
+//!
+//! let mut client = Client::new(
+//!     "client_id",
+//!     Url::parse("http://authorize")?,
+//!     Url::parse("http://token")?
+//! );
+//!
+//! client.set_client_secret("client_secret");
+//! client.set_backchannel_authentication_url(Url::parse("http://bc-authorize")?);
+//! client.add_scope("openid");
+//!
+//! let response = client
+//!     .backchannel_authentication(AuthenticationHint::LoginHint("user@example.com".into()))?
+//!     .param("binding_message", "W4SCT")
+//!     .with_reqwest_client(&reqwest_client)
+//!     .execute::<BackchannelAuthenticationResponse>()
+//!     .await?;
+//!
+//! let token = client
+//!     .poll_backchannel_authentication(
+//!         &response,
+//!         |request| {
+//!             request
+//!                 .with_reqwest_client(&reqwest_client)
+//!                 .execute::<StandardToken>()
+//!         },
+//!         tokio::time::sleep,
+//!     )
+//!     .await?;
+//!
+//! # Ok(()) }
+//! ```
+//!
+//! <br>
+//!
 //! ## Relationship to oauth2-rs
 //!
 //! This is a fork of [oauth2-rs].
@@ -219,6 +269,7 @@
 //! * Remove most newtypes except `Scope` and the secret ones since they made the API harder to use.
 //!
 //! [RFC 6749]: https://tools.ietf.org/html/rfc6749
+//! [CIBA]: https://openid.net/specs/openid-client-initiated-backchannel-authentication-core-1_0.html
 //! [Google]: https://github.com/udoprog/async-oauth2/blob/master/examples/src/bin/google.rs
 //! [oauth2-rs]: https://github.com/ramosbugs/oauth2-rs
 //! [reqwest]: https://docs.rs/reqwest
@@ -229,10 +280,25 @@
 #![allow(clippy::vec_init_then_push)]
 #![deny(missing_docs)]
 #![no_std]
+// Without a transport requests can be built but not executed, which leaves
+// most of what's needed to execute them unused.
+#![cfg_attr(
+    not(any(
+        feature = "reqwest",
+        feature = "hyper",
+        feature = "ureq",
+        feature = "wasm",
+        feature = "recording"
+    )),
+    allow(dead_code)
+)]
 
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
+#[cfg(feature = "std")]
+extern crate std;
+
 #[cfg(not(feature = "alloc"))]
 compile_error!("The `alloc` feature is required for async-oauth2 to work. Please enable it in your Cargo.toml.");
 
@@ -242,11 +308,13 @@ use core::ops::Deref;
 use core::time::Duration;
 
 use alloc::borrow::{Cow, ToOwned};
+use alloc::boxed::Box;
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 
 use base64::prelude::{Engine as _, BASE64_URL_SAFE_NO_PAD};
 use bytes::Bytes;
+use http::header::HeaderMap;
 use http::status::StatusCode;
 use serde::{Deserialize, Serialize};
 use serde_aux::prelude::*;
@@ -345,6 +413,287 @@ macro_rules! newtype {
 pub struct Scope(String);
 newtype!(Scope, String, str);
 
+/// A set of access token scopes, as defined in
+/// [Section 3.3 of RFC 6749](https://tools.ietf.org/html/rfc6749#section-3.3).
+///
+/// Scopes are kept in the order in which they were first inserted, and
+/// duplicates are ignored. When serialized, the set is represented as a
+/// space-delimited string.
+///
+/// # Examples
+///
+/// ```
+/// use oauth2::Scopes;
+///
+/// let requested: Scopes = "read  write admin".parse()?;
+/// let granted: Scopes = "read write".parse()?;
+///
+/// assert!(granted.is_subset(&requested));
+/// assert_eq!(requested.difference(&granted).to_string(), "admin");
+/// assert!("read\twrite".parse::<Scopes>().is_err());
+/// # Ok::<_, oauth2::ParseScopesError>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Scopes {
+    scopes: Vec<Scope>,
+}
+
+impl Scopes {
+    /// Construct a new empty set of scopes.
+    pub const fn new() -> Self {
+        Self { scopes: Vec::new() }
+    }
+
+    /// Parse a space-delimited list of scopes.
+    ///
+    /// Repeated spaces are collapsed and duplicate scopes are ignored. Scopes
+    /// containing characters other than those permitted by
+    /// [Section 3.3 of RFC 6749](https://tools.ietf.org/html/rfc6749#section-3.3)
+    /// result in an error.
+    pub fn parse(scopes: &str) -> Result<Self, ParseScopesError> {
+        let mut output = Scopes::new();
+
+        for scope in scopes.split(' ') {
+            if scope.is_empty() {
+                continue;
+            }
+
+            if let Some(character) = scope.chars().find(|&c| !is_scope_char(c)) {
+                return Err(ParseScopesError { character });
+            }
+
+            output.insert(scope);
+        }
+
+        Ok(output)
+    }
+
+    /// Insert a scope, returning `true` if it wasn't already present.
+    ///
+    /// Unlike [`Scopes::parse`], the scope is not validated. A scope which
+    /// contains a space is therefore sent as multiple scopes.
+    pub fn insert(&mut self, scope: impl Into<Scope>) -> bool {
+        let scope = scope.into();
+
+        if self.contains(&scope) {
+            return false;
+        }
+
+        self.scopes.push(scope);
+        true
+    }
+
+    /// Remove a scope, returning `true` if it was present.
+    pub fn remove(&mut self, scope: &str) -> bool {
+        let len = self.scopes.len();
+        self.scopes.retain(|s| **s != *scope);
+        self.scopes.len() != len
+    }
+
+    /// Test if the set contains the given scope.
+    pub fn contains(&self, scope: &str) -> bool {
+        self.scopes.iter().any(|s| **s == *scope)
+    }
+
+    /// Get the number of scopes in the set.
+    pub fn len(&self) -> usize {
+        self.scopes.len()
+    }
+
+    /// Test if the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.scopes.is_empty()
+    }
+
+    /// Iterate over the scopes in the set.
+    pub fn iter(&self) -> core::slice::Iter<'_, Scope> {
+        self.scopes.iter()
+    }
+
+    /// Test if every scope in this set is also present in `other`.
+    pub fn is_subset(&self, other: &Scopes) -> bool {
+        self.iter().all(|s| other.contains(s))
+    }
+
+    /// Test if every scope in `other` is also present in this set.
+    pub fn is_superset(&self, other: &Scopes) -> bool {
+        other.is_subset(self)
+    }
+
+    /// Construct the set of scopes in this set which are not present in
+    /// `other`.
+    pub fn difference(&self, other: &Scopes) -> Scopes {
+        self.iter()
+            .filter(|s| !other.contains(s))
+            .cloned()
+            .collect()
+    }
+
+    /// Construct the set of scopes which are present both in this set and in
+    /// `other`.
+    pub fn intersection(&self, other: &Scopes) -> Scopes {
+        self.iter().filter(|s| other.contains(s)).cloned().collect()
+    }
+}
+
+/// Test if the character is permitted in a scope token.
+///
+/// ```text
+/// scope-token = 1*( %x21 / %x23-5B / %x5D-7E )
+/// ```
+fn is_scope_char(c: char) -> bool {
+    matches!(c, '\x21' | '\x23'..='\x5b' | '\x5d'..='\x7e')
+}
+
+/// Scopes compare equal if they contain the same scopes, regardless of order.
+impl PartialEq for Scopes {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.is_subset(other)
+    }
+}
+
+impl Eq for Scopes {}
+
+impl fmt::Display for Scopes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut it = self.scopes.iter();
+
+        if let Some(scope) = it.next() {
+            f.write_str(scope)?;
+
+            for scope in it {
+                f.write_str(" ")?;
+                f.write_str(scope)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl core::str::FromStr for Scopes {
+    type Err = ParseScopesError;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Scopes::parse(s)
+    }
+}
+
+impl<S> FromIterator<S> for Scopes
+where
+    S: Into<Scope>,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+    {
+        let mut scopes = Scopes::new();
+        scopes.extend(iter);
+        scopes
+    }
+}
+
+impl<S> Extend<S> for Scopes
+where
+    S: Into<Scope>,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = S>,
+    {
+        for scope in iter {
+            self.insert(scope);
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a Scopes {
+    type Item = &'a Scope;
+    type IntoIter = core::slice::Iter<'a, Scope>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for Scopes {
+    type Item = Scope;
+    type IntoIter = alloc::vec::IntoIter<Scope>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.scopes.into_iter()
+    }
+}
+
+impl serde::Serialize for Scopes {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+/// Scopes are deserialized from a space-delimited string. For compatibility
+/// with providers which are off-spec, a sequence of strings is also accepted,
+/// scopes may be delimited by any whitespace, and scopes are not checked for
+/// illegal characters.
+impl<'de> serde::Deserialize<'de> for Scopes {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        return deserializer.deserialize_any(ScopesVisitor);
+
+        struct ScopesVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for ScopesVisitor {
+            type Value = Scopes;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a space-delimited string of scopes")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(value.split_ascii_whitespace().collect())
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::SeqAccess<'de>,
+            {
+                let mut scopes = Scopes::new();
+
+                while let Some(scope) = seq.next_element::<Cow<'de, str>>()? {
+                    scopes.extend(scope.split_ascii_whitespace());
+                }
+
+                Ok(scopes)
+            }
+        }
+    }
+}
+
+/// Error raised when parsing [`Scopes`] which contain illegal characters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseScopesError {
+    character: char,
+}
+
+impl fmt::Display for ParseScopesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "illegal character {:?} in scope", self.character)
+    }
+}
+
+impl Error for ParseScopesError {}
+
 /// Code Challenge used for [PKCE]((https://tools.ietf.org/html/rfc7636)) protection via the
 /// `code_challenge` parameter.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
@@ -374,11 +723,11 @@ borrowed_newtype!(State, [u8]);
 
 impl State {
     /// Generate a new random, base64-encoded 128-bit CSRF token.
-    #[cfg(feature = "rand")]
+    #[cfg(any(feature = "rand", feature = "wasm"))]
     #[inline]
     pub fn new_random() -> Self {
         let mut random_bytes = [0u8; 16];
-        rand::fill(&mut random_bytes);
+        fill_random(&mut random_bytes);
         State(random_bytes)
     }
 
@@ -425,6 +774,18 @@ impl<'de> serde::Deserialize<'de> for State {
     }
 }
 
+/// Fill the given buffer with cryptographically secure random bytes.
+#[cfg(feature = "rand")]
+fn fill_random(bytes: &mut [u8]) {
+    rand::fill(bytes);
+}
+
+/// Fill the given buffer with cryptographically secure random bytes.
+#[cfg(all(feature = "wasm", not(feature = "rand")))]
+fn fill_random(bytes: &mut [u8]) {
+    wasm::fill_random(bytes);
+}
+
 /// Code Verifier used for [PKCE]((https://tools.ietf.org/html/rfc7636)) protection via the
 /// `code_verifier` parameter. The value must have a minimum length of 43 characters and a
 /// maximum length of 128 characters.  Each character must be ASCII alphanumeric or one of
@@ -435,7 +796,7 @@ newtype!(PkceCodeVerifierS256, String, str);
 
 impl PkceCodeVerifierS256 {
     /// Generate a new random, base64-encoded code verifier.
-    #[cfg(feature = "rand")]
+    #[cfg(any(feature = "rand", feature = "wasm"))]
     pub fn new_random() -> Self {
         PkceCodeVerifierS256::new_random_len(32)
     }
@@ -447,13 +808,15 @@ impl PkceCodeVerifierS256 {
     /// * `num_bytes` - Number of random bytes to generate, prior to base64-encoding.
     ///   The value must be in the range 32 to 96 inclusive in order to generate a verifier
     ///   with a suitable length.
-    #[cfg(feature = "rand")]
+    #[cfg(any(feature = "rand", feature = "wasm"))]
     pub fn new_random_len(num_bytes: u32) -> Self {
         // The RFC specifies that the code verifier must have "a minimum length of 43
         // characters and a maximum length of 128 characters".
         // This implies 32-96 octets of random data to be base64 encoded.
         assert!((32..=96).contains(&num_bytes));
-        let random_bytes: Vec<u8> = (0..num_bytes).map(|_| rand::random::<u8>()).collect();
+        let mut random_bytes = [0u8; 96];
+        let random_bytes = &mut random_bytes[..num_bytes as usize];
+        fill_random(random_bytes);
         let code = BASE64_URL_SAFE_NO_PAD.encode(random_bytes);
         assert!(code.len() >= 43 && code.len() <= 128);
         PkceCodeVerifierS256(code)
@@ -505,6 +868,66 @@ newtype!(AccessToken, String, str);
 pub struct ResourceOwnerPassword(String);
 newtype!(ResourceOwnerPassword, String, str);
 
+/// Identifier of a backchannel authentication request, returned by the
+/// backchannel authentication endpoint and used to poll for a token.
+#[derive(Clone, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AuthReqId(String);
+redacted_debug!(AuthReqId);
+newtype!(AuthReqId, String, str);
+
+/// Hint used to identify the end-user for whom backchannel authentication is
+/// being requested.
+///
+/// Exactly one hint must be provided, as defined in
+/// [Section 7.1 of CIBA](https://openid.net/specs/openid-client-initiated-backchannel-authentication-core-1_0.html#rfc.section.7.1).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AuthenticationHint {
+    /// A hint such as an email address, phone number or username, sent as
+    /// `login_hint`.
+    LoginHint(String),
+    /// A previously issued ID token, sent as `id_token_hint`.
+    IdTokenHint(String),
+    /// A token containing information identifying the end-user, sent as
+    /// `login_hint_token`.
+    LoginHintToken(String),
+}
+
+/// Successful response from the backchannel authentication endpoint.
+///
+/// The fields in this structure are defined in
+/// [Section 7.3 of CIBA](https://openid.net/specs/openid-client-initiated-backchannel-authentication-core-1_0.html#rfc.section.7.3).
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct BackchannelAuthenticationResponse {
+    auth_req_id: AuthReqId,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    expires_in: u64,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_option_number_from_string"
+    )]
+    interval: Option<u64>,
+}
+
+impl BackchannelAuthenticationResponse {
+    /// REQUIRED. The unique identifier of the authentication request, used
+    /// when polling the token endpoint.
+    pub fn auth_req_id(&self) -> &AuthReqId {
+        &self.auth_req_id
+    }
+
+    /// REQUIRED. The lifetime of the `auth_req_id`.
+    pub fn expires_in(&self) -> Duration {
+        Duration::from_secs(self.expires_in)
+    }
+
+    /// OPTIONAL. The minimum amount of time the client should wait between
+    /// polling requests to the token endpoint.
+    pub fn interval(&self) -> Option<Duration> {
+        self.interval.map(Duration::from_secs)
+    }
+}
+
 /// Stores the configuration for an OAuth2 client.
 #[derive(Clone, Debug)]
 pub struct Client {
@@ -513,8 +936,12 @@ pub struct Client {
     auth_url: Url,
     auth_type: AuthType,
     token_url: Url,
-    scopes: Vec<Scope>,
+    scopes: Scopes,
     redirect_url: Option<Url>,
+    backchannel_authentication_url: Option<Url>,
+    strict: bool,
+    detect_success_errors: bool,
+    timeout: Option<Duration>,
 }
 
 impl Client {
@@ -540,8 +967,12 @@ impl Client {
             auth_url,
             auth_type: AuthType::BasicAuth,
             token_url,
-            scopes: Vec::new(),
+            scopes: Scopes::new(),
             redirect_url: None,
+            backchannel_authentication_url: None,
+            strict: false,
+            detect_success_errors: false,
+            timeout: None,
         }
     }
 
@@ -551,8 +982,15 @@ impl Client {
     }
 
     /// Appends a new scope to the authorization URL.
+    ///
+    /// The scope is not validated, see [`Scopes::insert`].
     pub fn add_scope(&mut self, scope: impl Into<Scope>) {
-        self.scopes.push(scope.into());
+        self.scopes.insert(scope);
+    }
+
+    /// Access the scopes configured for the client.
+    pub fn scopes(&self) -> &Scopes {
+        &self.scopes
     }
 
     /// Configures the type of client authentication used for communicating with the authorization
@@ -569,6 +1007,115 @@ impl Client {
         self.redirect_url = Some(redirect_url);
     }
 
+    /// Sets the backchannel authentication endpoint used by the
+    /// [Client-Initiated Backchannel Authentication][CIBA] flow.
+    ///
+    /// [CIBA]: https://openid.net/specs/openid-client-initiated-backchannel-authentication-core-1_0.html
+    pub fn set_backchannel_authentication_url(&mut self, backchannel_authentication_url: Url) {
+        self.backchannel_authentication_url = Some(backchannel_authentication_url);
+    }
+
+    /// Configures whether the client should operate in strict mode, which
+    /// enforces the requirements of [OAuth 2.1].
+    ///
+    /// In strict mode:
+    /// * [`Client::try_authorize_url_implicit`] fails with a
+    ///   [`StrictViolation::ImplicitGrant`] error, since the Implicit Grant has
+    ///   been removed.
+    /// * [`AuthorizeRequest::try_build`] fails with a
+    ///   [`StrictViolation::MissingPkce`] error unless a PKCE challenge using
+    ///   the `S256` method has been added.
+    /// * Token requests using the Resource Owner Password Credentials Grant
+    ///   fail with a [`StrictViolation::PasswordGrant`] error.
+    /// * Authorization code exchanges without a PKCE `code_verifier` fail with
+    ///   a [`StrictViolation::MissingPkce`] error.
+    /// * Authorization code exchanges fail unless a redirect URL without a
+    ///   fragment has been configured, so that the server can perform exact
+    ///   redirect URL matching.
+    /// * Token requests fail with a [`StrictViolation::BearerTokenInQuery`]
+    ///   error if the URL they are sent to has an `access_token` query
+    ///   parameter.
+    /// * Helpers constructed through [`Client::bearer`] refuse to send access
+    ///   tokens as query parameters with a
+    ///   [`StrictViolation::BearerTokenInQuery`] error.
+    ///
+    /// Strict mode is disabled by default.
+    ///
+    /// [OAuth 2.1]: https://datatracker.ietf.org/doc/html/draft-ietf-oauth-v2-1
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// Configures whether successful responses containing an `error` member
+    /// should be treated as error responses.
+    ///
+    /// Some providers, such as GitHub, respond with `200 OK` and an error
+    /// body. When enabled, such responses result in an [`ExecuteError`]
+    /// containing the [`ErrorResponse`] rather than a failure to deserialize
+    /// the expected token.
+    ///
+    /// This is disabled by default, since token responses from providers which
+    /// follow [RFC 6749] are not expected to contain an `error` member. Enable
+    /// it for providers which are known to respond in this manner:
+    ///
+    /// ```
+    /// use oauth2::Client;
+    /// use url::Url;
+    ///
+    /// let mut client = Client::new(
+    ///     "client_id",
+    ///     Url::parse("https://github.com/login/oauth/authorize")?,
+    ///     Url::parse("https://github.com/login/oauth/access_token")?,
+    /// );
+    ///
+    /// client.set_detect_success_errors(true);
+    /// # Ok::<_, url::ParseError>(())
+    /// ```
+    ///
+    /// [RFC 6749]: https://tools.ietf.org/html/rfc6749
+    pub fn set_detect_success_errors(&mut self, detect_success_errors: bool) {
+        self.detect_success_errors = detect_success_errors;
+    }
+
+    /// Configures the timeout applied to each token request made through the
+    /// client.
+    ///
+    /// The timeout covers the whole request, from connecting until the
+    /// response body has been received, and takes precedence over any timeout
+    /// configured on the underlying transport. A request which times out
+    /// fails with an error of kind [`ErrorKind::Timeout`].
+    ///
+    /// How the timeout is enforced depends on the transport:
+    /// * reqwest delegates it to `RequestBuilder::timeout`.
+    /// * hyper wraps the whole request in a tokio timer.
+    /// * ureq configures the global timeout of the request.
+    /// * fetch aborts the request using an `AbortSignal`.
+    ///
+    /// The timeout can be overridden for individual requests through
+    /// [`Request::timeout`]. By default no timeout is configured.
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = Some(timeout);
+    }
+
+    /// Test if the client is operating in strict mode.
+    ///
+    /// See [`Client::set_strict`].
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Construct a helper which attaches the given token to requests sent to
+    /// a protected resource.
+    ///
+    /// In strict mode the helper refuses to send the token as a query
+    /// parameter, see [`Bearer::apply_query`].
+    pub fn bearer<'a, T>(&self, token: &'a T) -> Bearer<'a>
+    where
+        T: Token,
+    {
+        Bearer::new(token).strict(self.strict)
+    }
+
     /// Produces the full authorization URL used by the
     /// [Authorization Code Grant](https://tools.ietf.org/html/rfc6749#section-4.1)
     /// flow, which is the most common OAuth2 flow.
@@ -587,7 +1134,122 @@ impl Client {
     /// [Cross-Site Request Forgery](https://tools.ietf.org/html/rfc6749#section-10.12)
     /// attacks.
     pub fn authorize_url(&self, state: &State) -> Url {
-        self.authorize_url_impl("code", state)
+        self.authorize_request(state).build()
+    }
+
+    /// Constructs a builder for the authorization URL used by the
+    /// [Authorization Code Grant](https://tools.ietf.org/html/rfc6749#section-4.1)
+    /// flow, which permits adding extra parameters to the URL.
+    ///
+    /// See [`Client::authorize_url`] for details on the `state` argument.
+    pub fn authorize_request<'a>(&'a self, state: &'a State) -> AuthorizeRequest<'a> {
+        AuthorizeRequest {
+            client: self,
+            response_type: "code",
+            state,
+            scopes: Cow::Borrowed(&self.scopes),
+            params: Vec::new(),
+        }
+    }
+
+    /// Parse the URL the user-agent was redirected to by the authorization
+    /// server, returning the authorization code if the authorization
+    /// succeeded.
+    ///
+    /// Parameters are read from the query of the URL, or from its fragment if
+    /// the query contains neither a `code` nor an `error` parameter, which is
+    /// the case for `response_mode=fragment`.
+    ///
+    /// The `state` parameter of the callback is verified to match `state`,
+    /// which should be the same value that was passed to
+    /// [`Client::authorize_url`]. This is also done for error callbacks, so
+    /// that a forged error results in [`CallbackError::is_state_mismatch`].
+    /// Since some servers omit the `state` parameter from error callbacks, an
+    /// error callback without it is still returned as an error response.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use oauth2::{Client, State};
+    /// use url::Url;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = Client::new(
+    ///     "client_id",
+    ///     Url::parse("http://authorize")?,
+    ///     Url::parse("http://token")?
+    /// );
+    ///
+    /// let state = State::from_random([42; 16]);
+    ///
+    /// let url = Url::parse(&format!(
+    ///     "http://redirect?code=the-code&state={}",
+    ///     state.to_base64()
+    /// ))?;
+    ///
+    /// let code = client.parse_callback(&url, &state)?;
+    /// assert_eq!(&*code, "the-code");
+    ///
+    /// let url = Url::parse(&format!(
+    ///     "http://redirect?error=access_denied&state={}",
+    ///     state.to_base64()
+    /// ))?;
+    ///
+    /// let error = client.parse_callback(&url, &state).unwrap_err();
+    /// assert!(error.error_response().is_some());
+    ///
+    /// let url = Url::parse("http://redirect?error=access_denied&state=invalid")?;
+    /// let error = client.parse_callback(&url, &state).unwrap_err();
+    /// assert!(error.is_state_mismatch());
+    /// # Ok(()) }
+    /// ```
+    pub fn parse_callback(
+        &self,
+        url: &Url,
+        state: &State,
+    ) -> Result<AuthorizationCode, CallbackError> {
+        let is_callback = |(key, _): &(Cow<'_, str>, Cow<'_, str>)| key == "code" || key == "error";
+
+        let mut params = url.query_pairs().collect::<Vec<_>>();
+
+        if !params.iter().any(is_callback) {
+            if let Some(fragment) = url.fragment() {
+                params = url::form_urlencoded::parse(fragment.as_bytes()).collect();
+            }
+        }
+
+        let param = |name: &str| {
+            params
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.as_ref())
+        };
+
+        let received = param("state");
+
+        if received.is_some_and(|received| *received != state.to_base64()) {
+            return Err(CallbackError::from(CallbackErrorKind::StateMismatch));
+        }
+
+        if let Some(error) = param("error") {
+            return Err(CallbackError::from(CallbackErrorKind::ErrorResponse {
+                error: ErrorResponse {
+                    error: ErrorField::from_code(error.to_owned()),
+                    error_description: param("error_description").map(ToOwned::to_owned),
+                    error_uri: param("error_uri").map(ToOwned::to_owned),
+                },
+            }));
+        }
+
+        if received.is_none() {
+            return Err(CallbackError::from(CallbackErrorKind::MissingState));
+        }
+
+        let Some(code) = param("code") else {
+            return Err(CallbackError::from(CallbackErrorKind::MissingCode));
+        };
+
+        Ok(AuthorizationCode::from(code))
     }
 
     /// Produces the full authorization URL used by the
@@ -606,38 +1268,39 @@ impl Client {
     /// redirect URI. Doing so mitigates
     /// [Cross-Site Request Forgery](https://tools.ietf.org/html/rfc6749#section-10.12)
     ///  attacks.
+    ///
+    ///
+    /// This does not check [strict mode][Client::set_strict], use
+    /// [`Client::try_authorize_url_implicit`] to refuse the Implicit Grant when
+    /// it is enabled.
     pub fn authorize_url_implicit(&self, state: &State) -> Url {
-        self.authorize_url_impl("token", state)
-    }
-
-    fn authorize_url_impl(&self, response_type: &str, state: &State) -> Url {
-        let scopes = self
-            .scopes
-            .iter()
-            .map(|s| s.to_string())
-            .collect::<Vec<_>>()
-            .join(" ");
-
-        let mut url = self.auth_url.clone();
-
-        {
-            let mut query = url.query_pairs_mut();
-
-            query.append_pair("response_type", response_type);
-            query.append_pair("client_id", &self.client_id);
-
-            if let Some(ref redirect_url) = self.redirect_url {
-                query.append_pair("redirect_uri", redirect_url.as_str());
-            }
-
-            if !scopes.is_empty() {
-                query.append_pair("scope", &scopes);
-            }
+        AuthorizeRequest {
+            client: self,
+            response_type: "token",
+            state,
+            scopes: Cow::Borrowed(&self.scopes),
+            params: Vec::new(),
+        }
+        .build()
+    }
 
-            query.append_pair("state", &state.to_base64());
+    /// Produces the full authorization URL used by the
+    /// [Implicit Grant](https://tools.ietf.org/html/rfc6749#section-4.2) flow,
+    /// unless the client is in [strict mode][Client::set_strict].
+    ///
+    /// See [`Client::authorize_url_implicit`] for details on the `state`
+    /// argument.
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`StrictViolation::ImplicitGrant`] if the client is in
+    /// strict mode, since the Implicit Grant is not permitted by OAuth 2.1.
+    pub fn try_authorize_url_implicit(&self, state: &State) -> Result<Url, StrictViolation> {
+        if self.strict {
+            return Err(StrictViolation::ImplicitGrant);
         }
 
-        url
+        Ok(self.authorize_url_implicit(state))
     }
 
     /// Exchanges a code produced by a successful authorization process with an access token.
@@ -671,18 +1334,7 @@ impl Client {
             .param("username", username.to_string())
             .param("password", password.to_string());
 
-        // Generate the space-delimited scopes String before initializing params so that it has
-        // a long enough lifetime.
-        if !self.scopes.is_empty() {
-            let scopes = self
-                .scopes
-                .iter()
-                .map(|s| s.to_string())
-                .collect::<Vec<_>>()
-                .join(" ");
-
-            builder = builder.param("scope", scopes);
-        }
+        builder.scopes = Some(Cow::Borrowed(&self.scopes));
 
         builder
     }
@@ -695,18 +1347,7 @@ impl Client {
             .request_token()
             .param("grant_type", "client_credentials");
 
-        // Generate the space-delimited scopes String before initializing params so that it has
-        // a long enough lifetime.
-        if !self.scopes.is_empty() {
-            let scopes = self
-                .scopes
-                .iter()
-                .map(|s| s.to_string())
-                .collect::<Vec<_>>()
-                .join(" ");
-
-            builder = builder.param("scope", scopes);
-        }
+        builder.scopes = Some(Cow::Borrowed(&self.scopes));
 
         builder
     }
@@ -720,6 +1361,160 @@ impl Client {
             .param("refresh_token", refresh_token.to_string())
     }
 
+    /// Exchanges a SAML 2.0 assertion for an access token.
+    ///
+    /// The `assertion` is the serialized SAML 2.0 `<Assertion>` element, which
+    /// will be base64url-encoded before it is sent.
+    ///
+    /// See https://tools.ietf.org/html/rfc7522#section-2.1
+    pub fn exchange_saml2_bearer(&self, assertion: impl AsRef<[u8]>) -> Request<'_> {
+        let assertion = BASE64_URL_SAFE_NO_PAD.encode(assertion);
+
+        let mut builder = self
+            .request_token()
+            .param(
+                "grant_type",
+                "urn:ietf:params:oauth:grant-type:saml2-bearer",
+            )
+            .param("assertion", assertion);
+
+        builder.scopes = Some(Cow::Borrowed(&self.scopes));
+
+        builder
+    }
+
+    /// Sends a backchannel authentication request, which starts the
+    /// [Client-Initiated Backchannel Authentication][CIBA] flow for the user
+    /// identified by `hint`.
+    ///
+    /// The response should be deserialized as a
+    /// [`BackchannelAuthenticationResponse`], whose `auth_req_id` is then
+    /// exchanged for a token using [`Client::exchange_backchannel`] or
+    /// [`Client::poll_backchannel_authentication`]. Optional parameters such as
+    /// `binding_message` or `requested_expiry` can be added through
+    /// [`Request::param`].
+    ///
+    /// # Errors
+    ///
+    /// Errors if no backchannel authentication endpoint has been configured
+    /// through [`Client::set_backchannel_authentication_url`].
+    ///
+    /// See https://openid.net/specs/openid-client-initiated-backchannel-authentication-core-1_0.html#auth_request
+    ///
+    /// [CIBA]: https://openid.net/specs/openid-client-initiated-backchannel-authentication-core-1_0.html
+    pub fn backchannel_authentication(
+        &self,
+        hint: AuthenticationHint,
+    ) -> Result<Request<'_>, MissingEndpoint> {
+        let Some(url) = &self.backchannel_authentication_url else {
+            return Err(MissingEndpoint {
+                name: "backchannel authentication",
+            });
+        };
+
+        let mut builder = Request {
+            token_url: url,
+            auth_type: self.auth_type,
+            client_id: &self.client_id,
+            client_secret: self.client_secret.as_ref(),
+            redirect_url: None,
+            strict: self.strict,
+            detect_success_errors: self.detect_success_errors,
+            timeout: self.timeout,
+            scopes: None,
+            params: Vec::new(),
+        };
+
+        builder.scopes = Some(Cow::Borrowed(&self.scopes));
+
+        let (key, value) = match hint {
+            AuthenticationHint::LoginHint(value) => ("login_hint", value),
+            AuthenticationHint::IdTokenHint(value) => ("id_token_hint", value),
+            AuthenticationHint::LoginHintToken(value) => ("login_hint_token", value),
+        };
+
+        Ok(builder.param(key, value))
+    }
+
+    /// Exchanges an `auth_req_id` obtained through
+    /// [`Client::backchannel_authentication`] for an access token.
+    ///
+    /// Until the user has approved the request, the authorization server
+    /// responds with [`ErrorField::AuthorizationPending`] or
+    /// [`ErrorField::SlowDown`]. See [`Client::poll_backchannel_authentication`]
+    /// for a helper which deals with this.
+    ///
+    /// See https://openid.net/specs/openid-client-initiated-backchannel-authentication-core-1_0.html#token_request
+    pub fn exchange_backchannel(&self, auth_req_id: &AuthReqId) -> Request<'_> {
+        self.request_token()
+            .param("grant_type", "urn:openid:params:grant-type:ciba")
+            .param("auth_req_id", auth_req_id.to_string())
+    }
+
+    /// Polls the token endpoint until the backchannel authentication request
+    /// described by `response` has been resolved.
+    ///
+    /// Requests are spaced out by the `interval` indicated in `response`, or
+    /// five seconds if the server didn't provide one. Each time the server
+    /// responds with [`ErrorField::SlowDown`] the interval is increased by
+    /// five seconds.
+    ///
+    /// Polling stops once the `expires_in` indicated in `response` would
+    /// elapse before the next request, in which case an error of kind
+    /// [`ErrorKind::Expired`] is returned. Time is measured by adding up the
+    /// intervals waited for.
+    ///
+    /// Each token request is executed through `execute`, which allows any
+    /// transport to be used. Since this crate doesn't depend on any particular
+    /// runtime, `sleep` is used to wait between requests. With tokio this
+    /// would be `tokio::time::sleep`.
+    pub async fn poll_backchannel_authentication<'a, T, E, G, S, F>(
+        &'a self,
+        response: &BackchannelAuthenticationResponse,
+        mut execute: E,
+        mut sleep: S,
+    ) -> Result<T, ExecuteError>
+    where
+        E: FnMut(Request<'a>) -> G,
+        G: core::future::Future<Output = Result<T, ExecuteError>>,
+        S: FnMut(Duration) -> F,
+        F: core::future::Future<Output = ()>,
+    {
+        // Default interval and slow down increment as defined in Section 7.3
+        // and Section 11 of CIBA respectively.
+        const DEFAULT_INTERVAL: Duration = Duration::from_secs(5);
+        const SLOW_DOWN: Duration = Duration::from_secs(5);
+
+        let expires_in = response.expires_in();
+        let mut interval = response.interval().unwrap_or(DEFAULT_INTERVAL);
+        let mut elapsed = Duration::ZERO;
+
+        loop {
+            elapsed = elapsed.saturating_add(interval);
+
+            if elapsed > expires_in {
+                return Err(ExecuteError::from(ExecuteErrorKind::BackchannelExpired));
+            }
+
+            sleep(interval).await;
+
+            let result = execute(self.exchange_backchannel(response.auth_req_id())).await;
+
+            let error = match result {
+                Ok(token) => return Ok(token),
+                Err(error) => error,
+            };
+
+            match error.error_response().map(|error| &error.error) {
+                Some(ErrorField::AuthorizationPending) => {}
+                Some(ErrorField::SlowDown) => {
+                    interval += SLOW_DOWN;
+                }
+                _ => return Err(error),
+            }
+        }
+    }
+
     /// Construct a request builder for the token URL.
     fn request_token(&self) -> Request<'_> {
         Request {
@@ -728,6 +1523,10 @@ impl Client {
             client_id: &self.client_id,
             client_secret: self.client_secret.as_ref(),
             redirect_url: self.redirect_url.as_ref(),
+            strict: self.strict,
+            detect_success_errors: self.detect_success_errors,
+            timeout: self.timeout,
+            scopes: None,
             params: Vec::new(),
         }
     }
@@ -735,6 +1534,7 @@ impl Client {
 
 /// A request wrapped in a client, ready to be executed.
 #[cfg(feature = "reqwest")]
+#[derive(Clone)]
 pub struct ReqwestClientRequest<'a> {
     request: Request<'a>,
     client: &'a reqwest::Client,
@@ -743,124 +1543,620 @@ pub struct ReqwestClientRequest<'a> {
 #[cfg(feature = "reqwest")]
 impl ReqwestClientRequest<'_> {
     /// Execute the token request.
+    ///
+    /// The timeout configured through [`Client::set_timeout`] or
+    /// [`Request::timeout`] is delegated to [`reqwest::RequestBuilder::timeout`].
+    ///
+    /// # Cancellation
+    ///
+    /// Dropping the returned future before it completes, for example due to
+    /// an external timeout, is safe. The request doesn't hold any state shared
+    /// with the [`Client`] it was constructed from, so no local state is left
+    /// behind and both can be used again.
+    ///
+    /// The server might however already have processed the request. If the
+    /// server rotates refresh tokens, the state of the grant is unknown after
+    /// a refresh which was cancelled or timed out: the refresh token used may
+    /// have been revoked without the new one ever being received. Replaying
+    /// the old refresh token is not safe, since servers which detect refresh
+    /// token reuse revoke the whole grant. Callers should instead expect the
+    /// grant to be gone and request authorization again, which a subsequent
+    /// refresh failing with [`ErrorField::InvalidGrant`] confirms.
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use oauth2::*;
+    /// use url::Url;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let reqwest_client = reqwest::Client::new();
+    ///
+    /// let mut client = Client::new(
+    ///     "client_id",
+    ///     Url::parse("http://authorize")?,
+    ///     Url::parse("http://token")?
+    /// );
+    ///
+    /// client.set_timeout(Duration::from_secs(10));
+    ///
+    /// let mut refresh_token = Some(RefreshToken::from("refresh_token"));
+    ///
+    /// if let Some(current) = refresh_token.take() {
+    ///     match client
+    ///         .exchange_refresh_token(&current)
+    ///         .with_reqwest_client(&reqwest_client)
+    ///         .execute::<StandardToken>()
+    ///         .await
+    ///     {
+    ///         Ok(token) => {
+    ///             // Servers which don't rotate refresh tokens omit them.
+    ///             refresh_token = Some(token.refresh_token().cloned().unwrap_or(current));
+    ///         }
+    ///         Err(error) if error.is_timeout() || error.is_invalid_grant() => {
+    ///             // The refresh token might have been consumed, so it's
+    ///             // discarded and authorization has to be requested again.
+    ///         }
+    ///         Err(error) => return Err(error.into()),
+    ///     }
+    /// }
+    ///
+    /// if refresh_token.is_none() {
+    ///     let url = client.authorize_url(&State::new_random());
+    ///     // Redirect the user to `url`.
+    /// #   let _ = url;
+    /// }
+    /// # Ok(()) }
+    /// ```
     pub async fn execute<T>(self) -> Result<T, ExecuteError>
     where
         T: for<'de> Deserialize<'de>,
     {
-        use reqwest::{header, Method};
+        let (headers, body) = self.request.encode()?;
 
-        const CONTENT_TYPE_JSON: &str = "application/json";
+        let mut request = self
+            .client
+            .post(self.request.token_url.clone())
+            .headers(headers)
+            .body(body);
 
-        fn url_encode(s: &str) -> String {
-            url::form_urlencoded::byte_serialize(s.as_bytes()).collect::<String>()
+        if let Some(timeout) = self.request.timeout {
+            request = request.timeout(timeout);
         }
 
-        let mut request = self
-            .client
-            .request(Method::POST, self.request.token_url.clone());
-
-        // Section 5.1 of RFC 6749 (https://tools.ietf.org/html/rfc6749#section-5.1) only permits
-        // JSON responses for this request. Some providers such as GitHub have off-spec behavior
-        // and not only support different response formats, but have non-JSON defaults. Explicitly
-        // request JSON here.
-        request = request.header(
-            header::ACCEPT,
-            header::HeaderValue::from_static(CONTENT_TYPE_JSON),
-        );
-
-        let request = {
-            let mut form = url::form_urlencoded::Serializer::new(String::new());
-
-            // FIXME: add support for auth extensions? e.g., client_secret_jwt and private_key_jwt
-            match self.request.auth_type {
-                AuthType::RequestBody => {
-                    form.append_pair("client_id", self.request.client_id);
-
-                    if let Some(client_secret) = self.request.client_secret {
-                        form.append_pair("client_secret", client_secret);
-                    }
-                }
-                AuthType::BasicAuth => {
-                    // Section 2.3.1 of RFC 6749 requires separately url-encoding the id and secret
-                    // before using them as HTTP Basic auth username and password. Note that this is
-                    // not standard for ordinary Basic auth, so curl won't do it for us.
-                    let username = url_encode(self.request.client_id);
-
-                    let password = self
-                        .request
-                        .client_secret
-                        .map(|client_secret| url_encode(client_secret));
-
-                    request = request.basic_auth(username, password.as_ref());
-                }
-            }
+        let res = request.send().await.map_err(ExecuteErrorKind::send_error)?;
 
-            for (key, value) in self.request.params {
-                form.append_pair(key.as_ref(), value.as_ref());
-            }
+        let status = res.status();
 
-            if let Some(redirect_url) = &self.request.redirect_url {
-                form.append_pair("redirect_uri", redirect_url.as_str());
+        let headers = res.headers().clone();
+
+        let body = res.bytes().await.map_err(ExecuteErrorKind::bytes_error)?;
+
+        transport::parse_response(status, headers, body, self.request.detect_success_errors)
+    }
+
+    /// Execute the token request, retrying according to `policy` on failures
+    /// which are likely to be transient.
+    ///
+    /// Since this crate doesn't depend on any particular runtime, `sleep` is
+    /// used to wait between attempts. With tokio this would be
+    /// `tokio::time::sleep`.
+    ///
+    /// See [`RetryPolicy`] for which failures are retried.
+    pub async fn execute_with_retry<T, S, F>(
+        self,
+        policy: &RetryPolicy,
+        mut sleep: S,
+    ) -> Result<T, ExecuteError>
+    where
+        T: for<'de> Deserialize<'de>,
+        S: FnMut(Duration) -> F,
+        F: core::future::Future<Output = ()>,
+    {
+        let mut attempt = 0;
+
+        loop {
+            let error = match self.clone().execute().await {
+                Ok(value) => return Ok(value),
+                Err(error) => error,
+            };
+
+            if attempt >= policy.max_retries || !policy.is_retryable(&self.request, &error) {
+                return Err(error);
             }
 
-            request = request.header(
-                header::CONTENT_TYPE,
-                header::HeaderValue::from_static("application/x-www-form-urlencoded"),
-            );
+            let Some(delay) = policy.delay(attempt, &error) else {
+                return Err(error);
+            };
 
-            request.body(form.finish().into_bytes())
+            sleep(delay).await;
+            attempt += 1;
+        }
+    }
+}
+
+/// A request wrapped in a [hyper] client, ready to be executed.
+///
+/// The client is expected to use [`Full<Bytes>`] request bodies, as
+/// constructed through:
+///
+/// ```no_run
+/// use bytes::Bytes;
+/// use http_body_util::Full;
+/// use hyper_util::client::legacy::Client;
+/// use hyper_util::rt::TokioExecutor;
+///
+/// let client = Client::builder(TokioExecutor::new()).build_http::<Full<Bytes>>();
+/// ```
+///
+/// [hyper]: https://docs.rs/hyper
+/// [`Full<Bytes>`]: http_body_util::Full
+#[cfg(feature = "hyper")]
+pub struct HyperClientRequest<'a, C> {
+    request: Request<'a>,
+    client: &'a hyper_util::client::legacy::Client<C, http_body_util::Full<Bytes>>,
+}
+
+#[cfg(feature = "hyper")]
+impl<C> Clone for HyperClientRequest<'_, C> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            request: self.request.clone(),
+            client: self.client,
+        }
+    }
+}
+
+#[cfg(feature = "hyper")]
+impl<C> HyperClientRequest<'_, C>
+where
+    C: hyper_util::client::legacy::connect::Connect + Clone + Send + Sync + 'static,
+{
+    /// Execute the token request.
+    ///
+    /// The request is identical to the one sent by
+    /// [`ReqwestClientRequest::execute`], and errors are classified the same
+    /// way. Timeouts configured through [`Client::set_timeout`] or
+    /// [`Request::timeout`] are enforced using the tokio timer.
+    ///
+    /// See [`ReqwestClientRequest::execute`] for the effects of cancelling the
+    /// returned future.
+    pub async fn execute<T>(self) -> Result<T, ExecuteError>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let future = self.send();
+
+        let (status, headers, body) = match self.request.timeout {
+            Some(timeout) => match tokio::time::timeout(timeout, future).await {
+                Ok(result) => result?,
+                Err(..) => return Err(ExecuteError::from(ExecuteErrorKind::Timeout)),
+            },
+            None => future.await?,
         };
 
-        let res = request
-            .send()
+        transport::parse_response(status, headers, body, self.request.detect_success_errors)
+    }
+
+    /// Send the request and collect the response.
+    async fn send(&self) -> Result<(StatusCode, HeaderMap, Bytes), ExecuteError> {
+        use http_body_util::{BodyExt, Full};
+
+        let (headers, body) = self.request.encode()?;
+
+        let mut request = http::Request::post(self.request.token_url.as_str())
+            .body(Full::new(Bytes::from(body)))
+            .map_err(|error| ExecuteErrorKind::HyperRequestError { error })?;
+
+        *request.headers_mut() = headers;
+
+        let response = self
+            .client
+            .request(request)
             .await
-            .map_err(|error| ExecuteErrorKind::SendError { error })?;
+            .map_err(|error| ExecuteErrorKind::HyperSendError { error })?;
 
-        let status = res.status();
+        let (parts, body) = response.into_parts();
 
-        let body = res
-            .bytes()
+        let body = body
+            .collect()
             .await
-            .map_err(|error| ExecuteErrorKind::BytesError { error })?;
+            .map_err(|error| ExecuteErrorKind::HyperBytesError { error })?;
 
-        if body.is_empty() {
-            return Err(ExecuteError::from(ExecuteErrorKind::EmptyResponse {
-                status,
-            }));
+        Ok((parts.status, parts.headers, body.to_bytes()))
+    }
+}
+
+/// A request wrapped in a [ureq] agent, ready to be executed synchronously.
+///
+/// [ureq]: https://docs.rs/ureq
+#[cfg(feature = "ureq")]
+#[derive(Clone)]
+pub struct UreqClientRequest<'a> {
+    request: Request<'a>,
+    agent: &'a ureq::Agent,
+}
+
+#[cfg(feature = "ureq")]
+impl UreqClientRequest<'_> {
+    /// Execute the token request, blocking the current thread until it has
+    /// completed.
+    ///
+    /// The request is identical to the one sent by
+    /// [`ReqwestClientRequest::execute`], and errors are classified the same
+    /// way. Responses with error status codes are handled by this crate
+    /// regardless of how the agent is configured.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use oauth2::*;
+    /// use url::Url;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let agent = ureq::Agent::new_with_defaults();
+    ///
+    /// let mut client = Client::new(
+    ///     "client_id",
+    ///     Url::parse("http://authorize")?,
+    ///     Url::parse("http://token")?
+    /// );
+    ///
+    /// client.set_client_secret("client_secret");
+    ///
+    /// let token = client
+    ///     .exchange_client_credentials()
+    ///     .with_ureq_agent(&agent)
+    ///     .execute::<StandardToken>()?;
+    /// # Ok(()) }
+    /// ```
+    pub fn execute<T>(self) -> Result<T, ExecuteError>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let (headers, body) = self.request.encode()?;
+
+        let mut request = self.agent.post(self.request.token_url.as_str());
+
+        if let Some(request_headers) = request.headers_mut() {
+            request_headers.extend(headers);
         }
 
-        if !status.is_success() {
-            let error = match serde_json::from_slice::<ErrorResponse>(body.as_ref()) {
-                Ok(error) => error,
-                Err(error) => {
-                    return Err(ExecuteError::from(ExecuteErrorKind::BadResponse {
-                        status,
-                        error,
-                        body,
-                    }));
-                }
-            };
+        let request = request
+            .config()
+            .http_status_as_error(false)
+            .timeout_global(self.request.timeout)
+            .build();
 
-            return Err(ExecuteError::from(ExecuteErrorKind::ErrorResponse {
-                status,
-                error,
-            }));
+        let response = request
+            .send(body)
+            .map_err(ExecuteErrorKind::ureq_send_error)?;
+
+        let (parts, mut body) = response.into_parts();
+
+        let body = body
+            .read_to_vec()
+            .map_err(ExecuteErrorKind::ureq_bytes_error)?;
+
+        transport::parse_response(
+            parts.status,
+            parts.headers,
+            Bytes::from(body),
+            self.request.detect_success_errors,
+        )
+    }
+}
+
+/// Policy used to retry token requests through
+/// [`ReqwestClientRequest::execute_with_retry`].
+///
+/// The following failures are considered transient and are retried:
+/// * Failures to connect to the server.
+/// * Other transport errors and timeouts, but only for idempotent requests.
+/// * Responses with a `5xx` or `429 Too Many Requests` status, unless the
+///   server responded with a known error code other than `server_error` or
+///   `temporarily_unavailable`.
+/// * Error responses with the `server_error` or `temporarily_unavailable`
+///   error codes.
+///
+/// Any other error, such as [`ErrorField::InvalidGrant`], is returned
+/// immediately.
+///
+/// A request which fails with a transport error or a timeout after it has
+/// been sent might still have been processed by the server. Authorization
+/// codes can only be used once, and servers which rotate refresh tokens
+/// revoke the whole grant if a refresh token is used twice. Only requests
+/// using the [Client Credentials Grant] are therefore considered idempotent.
+///
+/// Between attempts the policy waits for the duration indicated by the
+/// `Retry-After` header of the response if present, or otherwise for an
+/// exponentially increasing backoff. With the `rand` feature enabled, the
+/// backoff is randomized to avoid many clients retrying at the same time.
+///
+/// If the `Retry-After` header indicates a delay longer than the maximum
+/// backoff, the error is returned instead of retrying early. The same
+/// applies if the header uses the HTTP-date form, which is not supported.
+///
+/// [Client Credentials Grant]: https://tools.ietf.org/html/rfc6749#section-4.4
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    max_retries: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Construct a new retry policy which retries up to three times, with a
+    /// backoff starting at 100 milliseconds and capped at 10 seconds.
+    pub const fn new() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(10),
         }
+    }
+
+    /// Set the maximum number of retries after the initial attempt.
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Set the backoff used before the first retry, which is doubled for
+    /// every subsequent retry.
+    pub fn initial_backoff(mut self, initial_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self
+    }
+
+    /// Set the maximum duration to wait between attempts.
+    ///
+    /// This also bounds the delay which the server may request through the
+    /// `Retry-After` header. If the header indicates a longer delay, the
+    /// policy gives up and the error is returned instead of waiting for the
+    /// requested delay or retrying early. Since only the delay-seconds form of
+    /// the header is supported, the policy also gives up if the header uses
+    /// the HTTP-date form, regardless of the date it indicates.
+    pub fn max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
 
-        let value = serde_json::from_slice(body.as_ref()).map_err(|error| {
-            ExecuteErrorKind::BadResponse {
-                status,
-                error,
-                body,
+    /// Test if `request`, which failed with the given error, should be
+    /// retried.
+    pub fn is_retryable(&self, request: &Request<'_>, error: &ExecuteError) -> bool {
+        if let Some(response) = error.error_response() {
+            match response.error {
+                ErrorField::ServerError | ErrorField::TemporarilyUnavailable => return true,
+                ErrorField::Other(..) => {}
+                _ => return false,
             }
-        })?;
+        }
+
+        match error.kind() {
+            ErrorKind::Transport => error.is_connect() || request.is_idempotent(),
+            ErrorKind::Timeout => request.is_idempotent(),
+            ErrorKind::Strict => false,
+            _ => error.status().is_some_and(|status| {
+                status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+            }),
+        }
+    }
+
+    /// Calculate the delay before the retry following the given zero-based
+    /// `attempt` which failed with `error`.
+    ///
+    /// Returns `None` if the request shouldn't be retried, since the
+    /// `Retry-After` header of the response indicates a delay longer than
+    /// the maximum backoff or uses the unsupported HTTP-date form.
+    pub fn delay(&self, attempt: u32, error: &ExecuteError) -> Option<Duration> {
+        let has_retry_after = error
+            .headers()
+            .is_some_and(|headers| headers.contains_key(http::header::RETRY_AFTER));
+
+        if has_retry_after {
+            return error
+                .retry_after()
+                .filter(|retry_after| *retry_after <= self.max_backoff);
+        }
+
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_backoff);
+
+        Some(jitter(backoff))
+    }
+}
 
-        Ok(value)
+impl Default for RetryPolicy {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Randomize the given backoff to somewhere between half of it and all of it.
+#[cfg(feature = "rand")]
+fn jitter(backoff: Duration) -> Duration {
+    let half = backoff / 2;
+    let nanos = u64::try_from(half.as_nanos()).unwrap_or(u64::MAX);
+    half + Duration::from_nanos(rand::random_range(0..=nanos))
+}
+
+#[cfg(not(feature = "rand"))]
+fn jitter(backoff: Duration) -> Duration {
+    backoff
+}
+
+/// An authorization URL that is being built.
+///
+/// This is constructed through [`Client::authorize_request`].
+pub struct AuthorizeRequest<'a> {
+    client: &'a Client,
+    response_type: &'static str,
+    state: &'a State,
+    /// Requested scopes.
+    scopes: Cow<'a, Scopes>,
+    /// Extra parameters.
+    params: Vec<(Cow<'a, str>, Cow<'a, str>)>,
+}
+
+impl<'a> AuthorizeRequest<'a> {
+    /// Set an additional authorization request param.
+    pub fn param(mut self, key: impl Into<Cow<'a, str>>, value: impl Into<Cow<'a, str>>) -> Self {
+        self.params.push((key.into(), value.into()));
+        self
+    }
+
+    /// Set the scopes to request, replacing the scopes configured on the
+    /// client.
+    ///
+    /// This can be used for incremental authorization, where additional
+    /// scopes are requested as they are needed.
+    pub fn scopes<I>(mut self, scopes: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<Scope>,
+    {
+        self.scopes = Cow::Owned(scopes.into_iter().collect());
+        self
+    }
+
+    /// Append a scope to request in addition to the scopes configured on the
+    /// client.
+    pub fn add_scope(mut self, scope: impl Into<Scope>) -> Self {
+        self.scopes.to_mut().insert(scope);
+        self
+    }
+
+    /// Set the `prompt` parameter, which is a space-delimited list of values
+    /// such as `none`, `login`, `consent` or `select_account`.
+    ///
+    /// See https://openid.net/specs/openid-connect-core-1_0.html#AuthRequest
+    pub fn prompt(self, prompt: impl Into<Cow<'a, str>>) -> Self {
+        self.param("prompt", prompt)
+    }
+
+    /// Set the `login_hint` parameter, used to hint to the authorization
+    /// server which identifier the user might use to log in.
+    ///
+    /// See https://openid.net/specs/openid-connect-core-1_0.html#AuthRequest
+    pub fn login_hint(self, login_hint: impl Into<Cow<'a, str>>) -> Self {
+        self.param("login_hint", login_hint)
+    }
+
+    /// Set the `max_age` parameter, which is the maximum allowable elapsed
+    /// time since the user was last actively authenticated.
+    ///
+    /// See https://openid.net/specs/openid-connect-core-1_0.html#AuthRequest
+    pub fn max_age(self, max_age: Duration) -> Self {
+        self.param("max_age", max_age.as_secs().to_string())
+    }
+
+    /// Set the `ui_locales` parameter, which is a space-delimited list of
+    /// preferred BCP47 language tags for the user interface.
+    ///
+    /// See https://openid.net/specs/openid-connect-core-1_0.html#AuthRequest
+    pub fn ui_locales(self, ui_locales: impl Into<Cow<'a, str>>) -> Self {
+        self.param("ui_locales", ui_locales)
+    }
+
+    /// Set the `acr_values` parameter, which is a space-delimited list of
+    /// requested Authentication Context Class Reference values.
+    ///
+    /// See https://openid.net/specs/openid-connect-core-1_0.html#AuthRequest
+    pub fn acr_values(self, acr_values: impl Into<Cow<'a, str>>) -> Self {
+        self.param("acr_values", acr_values)
+    }
+
+    /// Set the `response_mode` parameter, such as `query`, `fragment` or
+    /// `form_post`.
+    ///
+    /// See https://openid.net/specs/oauth-v2-multiple-response-types-1_0.html#ResponseModes
+    pub fn response_mode(self, response_mode: impl Into<Cow<'a, str>>) -> Self {
+        self.param("response_mode", response_mode)
+    }
+
+    /// Set the non-standard `access_type` parameter to `offline`, which is
+    /// used by providers such as Google to issue a refresh token.
+    pub fn access_type_offline(self) -> Self {
+        self.param("access_type", "offline")
+    }
+
+    /// Set the `code_challenge` and `code_challenge_method` parameters used for
+    /// [PKCE](https://tools.ietf.org/html/rfc7636) protection.
+    pub fn pkce(mut self, code_verifier: &PkceCodeVerifierS256) -> Self {
+        for (key, value) in code_verifier.authorize_url_params() {
+            self = self.param(key, value);
+        }
+
+        self
+    }
+
+    /// Build the full authorization URL.
+    ///
+    /// This does not check [strict mode][Client::set_strict], see
+    /// [`AuthorizeRequest::try_build`].
+    pub fn build(self) -> Url {
+        let client = self.client;
+
+        let mut url = client.auth_url.clone();
+
+        {
+            let mut query = url.query_pairs_mut();
+
+            query.append_pair("response_type", self.response_type);
+            query.append_pair("client_id", &client.client_id);
+
+            if let Some(ref redirect_url) = client.redirect_url {
+                query.append_pair("redirect_uri", redirect_url.as_str());
+            }
+
+            if !self.scopes.is_empty() {
+                query.append_pair("scope", &self.scopes.to_string());
+            }
+
+            query.append_pair("state", &self.state.to_base64());
+
+            for (key, value) in &self.params {
+                query.append_pair(key, value);
+            }
+        }
+
+        url
+    }
+
+    /// Build the full authorization URL, checking the requirements of
+    /// [strict mode][Client::set_strict].
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`StrictViolation::MissingPkce`] if the client is in strict
+    /// mode and no PKCE challenge using the `S256` method has been added
+    /// through [`AuthorizeRequest::pkce`].
+    pub fn try_build(self) -> Result<Url, StrictViolation> {
+        if self.client.strict {
+            let param = |name: &str| {
+                self.params
+                    .iter()
+                    .find(|(key, _)| key == name)
+                    .map(|(_, value)| value.as_ref())
+            };
+
+            if param("code_challenge_method") != Some("S256") || param("code_challenge").is_none() {
+                return Err(StrictViolation::MissingPkce);
+            }
+        }
+
+        Ok(self.build())
     }
 }
 
 /// A token request that is in progress.
-#[cfg_attr(not(any(feature = "reqwest")), allow(unused))]
+#[derive(Clone)]
 pub struct Request<'a> {
     token_url: &'a Url,
     auth_type: AuthType,
@@ -868,6 +2164,14 @@ pub struct Request<'a> {
     client_secret: Option<&'a ClientSecret>,
     /// Configured redirect URL.
     redirect_url: Option<&'a Url>,
+    /// If the request is subject to strict mode.
+    strict: bool,
+    /// If successful responses should be checked for an `error` member.
+    detect_success_errors: bool,
+    /// Timeout for the whole request.
+    timeout: Option<Duration>,
+    /// Requested scopes, if the `scope` parameter should be sent.
+    scopes: Option<Cow<'a, Scopes>>,
     /// Extra parameters.
     params: Vec<(Cow<'a, str>, Cow<'a, str>)>,
 }
@@ -879,6 +2183,46 @@ impl<'a> Request<'a> {
         self
     }
 
+    /// Set the scopes to request, replacing any scopes that would otherwise
+    /// have been sent.
+    ///
+    /// When refreshing a token, this can be used to request a subset of the
+    /// originally granted scopes as permitted by
+    /// [Section 6 of RFC 6749](https://tools.ietf.org/html/rfc6749#section-6).
+    pub fn scopes<I>(mut self, scopes: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<Scope>,
+    {
+        self.scopes = Some(Cow::Owned(scopes.into_iter().collect()));
+        self
+    }
+
+    /// Append a scope to request, in addition to any scopes that would
+    /// otherwise have been sent.
+    pub fn add_scope(mut self, scope: impl Into<Scope>) -> Self {
+        self.scopes
+            .get_or_insert_with(|| Cow::Owned(Scopes::new()))
+            .to_mut()
+            .insert(scope);
+        self
+    }
+
+    /// Set the timeout for this request, overriding any timeout configured
+    /// through [`Client::set_timeout`].
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Test if the request can safely be sent again if it's unknown whether
+    /// the server has processed it, see [`RetryPolicy`].
+    fn is_idempotent(&self) -> bool {
+        self.params
+            .iter()
+            .any(|(key, value)| key == "grant_type" && value == "client_credentials")
+    }
+
     /// Wrap the request in a client.
     #[cfg(feature = "reqwest")]
     pub fn with_reqwest_client(self, client: &'a reqwest::Client) -> ReqwestClientRequest<'a> {
@@ -887,11 +2231,60 @@ impl<'a> Request<'a> {
             client,
         }
     }
+
+    /// Wrap the request in a [ureq] agent, for executing the request
+    /// synchronously.
+    ///
+    /// [ureq]: https://docs.rs/ureq
+    #[cfg(feature = "ureq")]
+    pub fn with_ureq_agent(self, agent: &'a ureq::Agent) -> UreqClientRequest<'a> {
+        UreqClientRequest {
+            request: self,
+            agent,
+        }
+    }
+
+    /// Wrap the request for execution using the browser [`fetch`] API.
+    ///
+    /// [`fetch`]: https://developer.mozilla.org/en-US/docs/Web/API/Fetch_API
+    #[cfg(feature = "wasm")]
+    pub fn with_fetch(self) -> wasm::FetchRequest<'a> {
+        wasm::FetchRequest::new(self)
+    }
+
+    /// Wrap the request in a [`Recorder`], which records the request and
+    /// replays a canned response instead of sending it.
+    ///
+    /// [`Recorder`]: recording::Recorder
+    #[cfg(feature = "recording")]
+    pub fn with_recorder(
+        self,
+        recorder: &'a recording::Recorder,
+    ) -> recording::RecorderRequest<'a> {
+        recording::RecorderRequest::new(self, recorder)
+    }
+
+    /// Wrap the request in a [hyper] client.
+    ///
+    /// [hyper]: https://docs.rs/hyper
+    #[cfg(feature = "hyper")]
+    pub fn with_hyper_client<C>(
+        self,
+        client: &'a hyper_util::client::legacy::Client<C, http_body_util::Full<Bytes>>,
+    ) -> HyperClientRequest<'a, C> {
+        HyperClientRequest {
+            request: self,
+            client,
+        }
+    }
 }
 
 /// Basic OAuth2 authorization token types.
-#[derive(Clone, Debug, PartialEq, Serialize)]
-#[serde(rename_all = "lowercase")]
+///
+/// Token types are compared case insensitively when deserialized, since
+/// [Section 5.1 of RFC 6749](https://tools.ietf.org/html/rfc6749#section-5.1)
+/// specifies the `token_type` value as case insensitive.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum TokenType {
     /// Bearer token
     /// ([OAuth 2.0 Bearer Tokens - RFC 6750](https://tools.ietf.org/html/rfc6750)).
@@ -899,6 +2292,63 @@ pub enum TokenType {
     /// MAC ([OAuth 2.0 Message Authentication Code (MAC)
     /// Tokens](https://tools.ietf.org/html/draft-ietf-oauth-v2-http-mac-05)).
     Mac,
+    /// DPoP-bound access token
+    /// ([OAuth 2.0 Demonstrating Proof of Possession - RFC 9449](https://tools.ietf.org/html/rfc9449)).
+    DPoP,
+    /// Not applicable, used when the issued token is not an access token
+    /// ([OAuth 2.0 Token Exchange - RFC 8693](https://tools.ietf.org/html/rfc8693#section-2.2.1)).
+    NA,
+    /// Any other token type, with its original casing preserved.
+    Other(String),
+}
+
+impl TokenType {
+    /// Get the token type as it is represented in a token response.
+    pub fn as_str(&self) -> &str {
+        match self {
+            TokenType::Bearer => "bearer",
+            TokenType::Mac => "mac",
+            TokenType::DPoP => "DPoP",
+            TokenType::NA => "N_A",
+            TokenType::Other(value) => value,
+        }
+    }
+
+    /// Get the authentication scheme used in the `Authorization` header when
+    /// presenting a token of this type.
+    ///
+    /// Returns `None` for [`TokenType::NA`], since such tokens can't be used
+    /// to access protected resources. `None` is also returned for
+    /// [`TokenType::Mac`] and [`TokenType::Other`], since presenting them
+    /// requires more than a scheme followed by the token.
+    ///
+    /// Note that a [`TokenType::DPoP`] token must also be accompanied by a
+    /// proof of possession in the `DPoP` header, as specified by
+    /// [Section 7.1 of RFC 9449](https://tools.ietf.org/html/rfc9449#section-7.1).
+    pub fn authorization_scheme(&self) -> Option<&str> {
+        match self {
+            TokenType::Bearer => Some("Bearer"),
+            TokenType::DPoP => Some("DPoP"),
+            TokenType::Mac | TokenType::NA | TokenType::Other(..) => None,
+        }
+    }
+}
+
+impl fmt::Display for TokenType {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+impl serde::Serialize for TokenType {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.as_str().serialize(serializer)
+    }
 }
 
 impl<'de> serde::de::Deserialize<'de> for TokenType {
@@ -906,26 +2356,21 @@ impl<'de> serde::de::Deserialize<'de> for TokenType {
     where
         D: serde::de::Deserializer<'de>,
     {
-        let value = String::deserialize(deserializer)?.to_lowercase();
-
-        return match value.as_str() {
-            "bearer" => Ok(TokenType::Bearer),
-            "mac" => Ok(TokenType::Mac),
-            other => Err(serde::de::Error::custom(UnknownVariantError(
-                other.to_string(),
-            ))),
+        let value = String::deserialize(deserializer)?;
+
+        let token_type = if value.eq_ignore_ascii_case("bearer") {
+            TokenType::Bearer
+        } else if value.eq_ignore_ascii_case("mac") {
+            TokenType::Mac
+        } else if value.eq_ignore_ascii_case("dpop") {
+            TokenType::DPoP
+        } else if value.eq_ignore_ascii_case("n_a") {
+            TokenType::NA
+        } else {
+            TokenType::Other(value)
         };
 
-        #[derive(Debug)]
-        struct UnknownVariantError(String);
-
-        impl Error for UnknownVariantError {}
-
-        impl fmt::Display for UnknownVariantError {
-            fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-                write!(fmt, "unsupported variant: {}", self.0)
-            }
-        }
+        Ok(token_type)
     }
 }
 
@@ -958,80 +2403,416 @@ where
     /// [Section 6](https://tools.ietf.org/html/rfc6749#section-6).
     fn refresh_token(&self) -> Option<&RefreshToken>;
 
-    /// OPTIONAL, if identical to the scope requested by the client; otherwise, REQUIRED. The
-    /// scipe of the access token as described by
-    /// [Section 3.3](https://tools.ietf.org/html/rfc6749#section-3.3). If included in the response,
-    /// this space-delimited field is parsed into a `Vec` of individual scopes. If omitted from
-    /// the response, this field is `None`.
-    fn scopes(&self) -> Option<&Vec<Scope>>;
-}
+    /// OPTIONAL, if identical to the scope requested by the client; otherwise, REQUIRED. The
+    /// scipe of the access token as described by
+    /// [Section 3.3](https://tools.ietf.org/html/rfc6749#section-3.3). If included in the response,
+    /// this space-delimited field is parsed into a set of individual scopes. If omitted from
+    /// the response, this field is `None`.
+    fn scopes(&self) -> Option<&Scopes>;
+
+    /// Compare the scopes of the token with the scopes that were `requested`.
+    ///
+    /// If the response omitted the scope, it is treated as identical to the
+    /// requested scope as specified in
+    /// [Section 5.1 of RFC 6749](https://tools.ietf.org/html/rfc6749#section-5.1).
+    fn granted_scopes(&self, requested: &Scopes) -> GrantedScopes {
+        GrantedScopes::new(requested, self.scopes())
+    }
+}
+
+/// The outcome of comparing the scopes of a token with the scopes that were
+/// requested.
+///
+/// This is constructed through [`Token::granted_scopes`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrantedScopes {
+    scopes: Scopes,
+    granted: Scopes,
+    dropped: Scopes,
+    added: Scopes,
+}
+
+impl GrantedScopes {
+    /// Compare the `requested` scopes with the scopes in a token response,
+    /// where `None` indicates that the response omitted the scope.
+    pub fn new(requested: &Scopes, response: Option<&Scopes>) -> Self {
+        let scopes = response.unwrap_or(requested).clone();
+
+        Self {
+            granted: requested.intersection(&scopes),
+            dropped: requested.difference(&scopes),
+            added: scopes.difference(requested),
+            scopes,
+        }
+    }
+
+    /// All scopes of the token.
+    pub fn scopes(&self) -> &Scopes {
+        &self.scopes
+    }
+
+    /// Requested scopes which were granted.
+    pub fn granted(&self) -> &Scopes {
+        &self.granted
+    }
+
+    /// Requested scopes which were not granted.
+    pub fn dropped(&self) -> &Scopes {
+        &self.dropped
+    }
+
+    /// Scopes which were granted without being requested.
+    pub fn added(&self) -> &Scopes {
+        &self.added
+    }
+
+    /// Test if the token has exactly the requested scopes.
+    pub fn is_identical(&self) -> bool {
+        self.dropped.is_empty() && self.added.is_empty()
+    }
+}
+
+/// Standard OAuth2 token response.
+///
+/// This struct includes the fields defined in
+/// [Section 5.1 of RFC 6749](https://tools.ietf.org/html/rfc6749#section-5.1), as well as
+/// extensions defined by the `E` type parameter. The extension type is flattened into the
+/// token response, so its fields are deserialized alongside the standard ones.
+///
+/// Any other fields in the response are preserved and can be accessed through
+/// [`StandardToken::extra`].
+///
+/// # Examples
+///
+/// ```
+/// use oauth2::{StandardToken, Token};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Debug, Deserialize, Serialize)]
+/// struct OpenIdExtensions {
+///     id_token: String,
+///     #[serde(default)]
+///     refresh_token_expires_in: Option<u64>,
+/// }
+///
+/// let token: StandardToken<OpenIdExtensions> = serde_json::from_str(r#"{
+///     "access_token": "secret",
+///     "token_type": "Bearer",
+///     "id_token": "header.payload.signature"
+/// }"#)?;
+///
+/// assert_eq!(&**token.access_token(), "secret");
+/// assert_eq!(token.extensions().id_token, "header.payload.signature");
+/// assert!(token.extra().is_empty());
+///
+/// let token: StandardToken = serde_json::from_str(r#"{
+///     "access_token": "secret",
+///     "token_type": "Bearer",
+///     "team": {"id": "T12345"}
+/// }"#)?;
+///
+/// assert_eq!(token.extra()["team"]["id"], "T12345");
+/// # Ok::<_, serde_json::Error>(())
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct StandardToken<E = EmptyExtensions> {
+    access_token: AccessToken,
+    token_type: TokenType,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_option_number_from_string"
+    )]
+    expires_in: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    refresh_token: Option<RefreshToken>,
+    #[serde(rename = "scope")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    scopes: Option<Scopes>,
+    #[serde(flatten)]
+    extensions: E,
+    #[serde(flatten)]
+    extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl<E> StandardToken<E> {
+    /// Access the extension fields of the token response.
+    pub fn extensions(&self) -> &E {
+        &self.extensions
+    }
+
+    /// Convert into the extension fields of the token response.
+    pub fn into_extensions(self) -> E {
+        self.extensions
+    }
+
+    /// Access fields in the token response which are neither standard nor
+    /// part of the extension fields.
+    pub fn extra(&self) -> &serde_json::Map<String, serde_json::Value> {
+        &self.extra
+    }
+}
+
+/// Extension type for [`StandardToken`] which doesn't contain any fields.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+pub struct EmptyExtensions {}
+
+impl<E> Token for StandardToken<E>
+where
+    E: for<'de> Deserialize<'de>,
+{
+    /// REQUIRED. The access token issued by the authorization server.
+    fn access_token(&self) -> &AccessToken {
+        &self.access_token
+    }
+
+    /// REQUIRED. The type of the token issued as described in
+    /// [Section 7.1](https://tools.ietf.org/html/rfc6749#section-7.1).
+    /// Value is case insensitive and deserialized to the generic `TokenType` parameter.
+    fn token_type(&self) -> &TokenType {
+        &self.token_type
+    }
+
+    /// RECOMMENDED. The lifetime in seconds of the access token. For example, the value 3600
+    /// denotes that the access token will expire in one hour from the time the response was
+    /// generated. If omitted, the authorization server SHOULD provide the expiration time via
+    /// other means or document the default value.
+    fn expires_in(&self) -> Option<Duration> {
+        self.expires_in.map(Duration::from_secs)
+    }
+
+    /// OPTIONAL. The refresh token, which can be used to obtain new access tokens using the same
+    /// authorization grant as described in
+    /// [Section 6](https://tools.ietf.org/html/rfc6749#section-6).
+    fn refresh_token(&self) -> Option<&RefreshToken> {
+        self.refresh_token.as_ref()
+    }
+
+    /// OPTIONAL, if identical to the scope requested by the client; otherwise, REQUIRED. The
+    /// scipe of the access token as described by
+    /// [Section 3.3](https://tools.ietf.org/html/rfc6749#section-3.3). If included in the response,
+    /// this space-delimited field is parsed into a set of individual scopes. If omitted from
+    /// the response, this field is `None`.
+    fn scopes(&self) -> Option<&Scopes> {
+        self.scopes.as_ref()
+    }
+}
+
+/// A point in time, represented as the number of seconds since the Unix
+/// epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct Timestamp(u64);
+
+impl Timestamp {
+    /// Construct a timestamp from the number of seconds since the Unix epoch.
+    #[inline]
+    pub const fn from_unix_secs(secs: u64) -> Self {
+        Self(secs)
+    }
+
+    /// Get the number of seconds since the Unix epoch.
+    #[inline]
+    pub const fn as_unix_secs(&self) -> u64 {
+        self.0
+    }
+
+    /// Add a duration to the timestamp, saturating on overflow.
+    #[inline]
+    pub fn saturating_add(self, duration: Duration) -> Self {
+        Self(self.0.saturating_add(duration.as_secs()))
+    }
+
+    /// Subtract a duration from the timestamp, saturating at the Unix epoch.
+    #[inline]
+    pub fn saturating_sub(self, duration: Duration) -> Self {
+        Self(self.0.saturating_sub(duration.as_secs()))
+    }
+}
+
+/// A source of the current time.
+///
+/// This is implemented for any `Fn() -> Timestamp`, which can be used to
+/// provide a clock in environments without `std` or in tests. With the `std`
+/// feature enabled, [`SystemClock`] is available.
+pub trait Clock {
+    /// Get the current time.
+    fn now(&self) -> Timestamp;
+}
+
+impl<F> Clock for F
+where
+    F: Fn() -> Timestamp,
+{
+    #[inline]
+    fn now(&self) -> Timestamp {
+        self()
+    }
+}
+
+/// A [`Clock`] which uses the system time.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct SystemClock;
+
+#[cfg(feature = "std")]
+impl Clock for SystemClock {
+    fn now(&self) -> Timestamp {
+        let since_epoch = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default();
+
+        Timestamp(since_epoch.as_secs())
+    }
+}
+
+/// A token stamped with the time at which it was issued.
+///
+/// Since [`Token::expires_in`] is relative to when the token response was
+/// generated, it loses its meaning once a token has been persisted. This
+/// wrapper keeps track of the absolute time at which the token expires, and
+/// serializes it alongside the token.
+///
+/// # Examples
+///
+/// ```
+/// use core::time::Duration;
+/// use oauth2::{IssuedToken, StandardToken, Timestamp};
+///
+/// let token: StandardToken = serde_json::from_str(r#"{
+///     "access_token": "secret",
+///     "token_type": "Bearer",
+///     "expires_in": 3600
+/// }"#)?;
+///
+/// let clock = || Timestamp::from_unix_secs(1_000_000);
+/// let token = IssuedToken::now(token, &clock);
+///
+/// assert_eq!(token.expires_at(), Some(Timestamp::from_unix_secs(1_003_600)));
+/// assert!(!token.is_expired(clock()));
+/// assert!(token.expires_within(Duration::from_secs(3600), clock()));
+///
+/// let json = serde_json::to_string(&token)?;
+/// let token: IssuedToken<StandardToken> = serde_json::from_str(&json)?;
+/// assert!(token.is_expired(Timestamp::from_unix_secs(1_003_600)));
+/// # Ok::<_, serde_json::Error>(())
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct IssuedToken<T> {
+    #[serde(flatten)]
+    token: T,
+    issued_at: Timestamp,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    expires_at: Option<Timestamp>,
+}
+
+impl<T> IssuedToken<T>
+where
+    T: Token,
+{
+    /// Stamp a token as issued at the given time.
+    pub fn new(token: T, issued_at: Timestamp) -> Self {
+        let expires_at = token
+            .expires_in()
+            .map(|expires_in| issued_at.saturating_add(expires_in));
+
+        Self {
+            token,
+            issued_at,
+            expires_at,
+        }
+    }
+
+    /// Stamp a token as issued at the current time according to `clock`.
+    pub fn now(token: T, clock: &impl Clock) -> Self {
+        Self::new(token, clock.now())
+    }
+}
+
+impl<T> IssuedToken<T> {
+    /// Access the wrapped token.
+    pub fn token(&self) -> &T {
+        &self.token
+    }
+
+    /// Convert into the wrapped token.
+    pub fn into_token(self) -> T {
+        self.token
+    }
+
+    /// The time at which the token was issued.
+    pub fn issued_at(&self) -> Timestamp {
+        self.issued_at
+    }
+
+    /// The time at which the token expires, if the token response indicated
+    /// a lifetime.
+    pub fn expires_at(&self) -> Option<Timestamp> {
+        self.expires_at
+    }
 
-/// Standard OAuth2 token response.
-///
-/// This struct includes the fields defined in
-/// [Section 5.1 of RFC 6749](https://tools.ietf.org/html/rfc6749#section-5.1), as well as
-/// extensions defined by the `EF` type parameter.
-#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
-pub struct StandardToken {
-    access_token: AccessToken,
-    token_type: TokenType,
-    #[serde(
-        skip_serializing_if = "Option::is_none",
-        deserialize_with = "deserialize_option_number_from_string"
-    )]
-    expires_in: Option<u64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    refresh_token: Option<RefreshToken>,
-    #[serde(rename = "scope")]
-    #[serde(deserialize_with = "helpers::deserialize_space_delimited_vec")]
-    #[serde(serialize_with = "helpers::serialize_space_delimited_vec")]
-    #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(default)]
-    scopes: Option<Vec<Scope>>,
+    /// Test if the token has expired at the time `now`.
+    ///
+    /// Tokens without a known lifetime are never considered expired.
+    pub fn is_expired(&self, now: Timestamp) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+
+    /// Test if the token expires within `skew` of the time `now`, which is
+    /// useful to refresh tokens slightly before they expire.
+    pub fn expires_within(&self, skew: Duration, now: Timestamp) -> bool {
+        self.is_expired(now.saturating_add(skew))
+    }
 }
 
-impl Token for StandardToken {
-    /// REQUIRED. The access token issued by the authorization server.
+impl<T> Token for IssuedToken<T>
+where
+    T: Token,
+{
     fn access_token(&self) -> &AccessToken {
-        &self.access_token
+        self.token.access_token()
     }
 
-    /// REQUIRED. The type of the token issued as described in
-    /// [Section 7.1](https://tools.ietf.org/html/rfc6749#section-7.1).
-    /// Value is case insensitive and deserialized to the generic `TokenType` parameter.
     fn token_type(&self) -> &TokenType {
-        &self.token_type
+        self.token.token_type()
     }
 
-    /// RECOMMENDED. The lifetime in seconds of the access token. For example, the value 3600
-    /// denotes that the access token will expire in one hour from the time the response was
-    /// generated. If omitted, the authorization server SHOULD provide the expiration time via
-    /// other means or document the default value.
     fn expires_in(&self) -> Option<Duration> {
-        self.expires_in.map(Duration::from_secs)
+        self.token.expires_in()
     }
 
-    /// OPTIONAL. The refresh token, which can be used to obtain new access tokens using the same
-    /// authorization grant as described in
-    /// [Section 6](https://tools.ietf.org/html/rfc6749#section-6).
     fn refresh_token(&self) -> Option<&RefreshToken> {
-        self.refresh_token.as_ref()
+        self.token.refresh_token()
     }
 
-    /// OPTIONAL, if identical to the scope requested by the client; otherwise, REQUIRED. The
-    /// scipe of the access token as described by
-    /// [Section 3.3](https://tools.ietf.org/html/rfc6749#section-3.3). If included in the response,
-    /// this space-delimited field is parsed into a `Vec` of individual scopes. If omitted from
-    /// the response, this field is `None`.
-    fn scopes(&self) -> Option<&Vec<Scope>> {
-        self.scopes.as_ref()
+    fn scopes(&self) -> Option<&Scopes> {
+        self.token.scopes()
     }
 }
 
 /// These error types are defined in
-/// [Section 5.2 of RFC 6749](https://tools.ietf.org/html/rfc6749#section-5.2).
-#[derive(Debug, Clone, Deserialize, PartialEq, Eq, Serialize)]
-#[serde(rename_all = "snake_case")]
+/// [Section 5.2 of RFC 6749](https://tools.ietf.org/html/rfc6749#section-5.2), as well as in
+/// extensions for additional grants, resource servers and OpenID Connect.
+///
+/// Error codes which are not known are represented by [`ErrorField::Other`].
+///
+/// # Examples
+///
+/// ```
+/// use oauth2::ErrorField;
+///
+/// let error: ErrorField = serde_json::from_str(r#""use_dpop_nonce""#)?;
+/// assert_eq!(error, ErrorField::UseDpopNonce);
+///
+/// let error: ErrorField = serde_json::from_str(r#""bad_verification_code""#)?;
+/// assert_eq!(error, ErrorField::Other("bad_verification_code".into()));
+/// assert_eq!(serde_json::to_string(&error)?, r#""bad_verification_code""#);
+/// # Ok::<_, serde_json::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum ErrorField {
     /// The request is missing a required parameter, includes an unsupported parameter value
     /// (other than grant type), repeats a parameter, includes multiple credentials, utilizes
@@ -1051,26 +2832,179 @@ pub enum ErrorField {
     /// The requested scope is invalid, unknown, malformed, or exceeds the scope granted by the
     /// resource owner.
     InvalidScope,
+    /// The authorization request is still pending as the end-user hasn't yet been authenticated.
+    ///
+    /// Defined in
+    /// [Section 11 of CIBA](https://openid.net/specs/openid-client-initiated-backchannel-authentication-core-1_0.html#rfc.section.11).
+    AuthorizationPending,
+    /// A variant of `authorization_pending`, the authorization request is still pending and
+    /// polling should continue, but the interval must be increased.
+    ///
+    /// Defined in
+    /// [Section 11 of CIBA](https://openid.net/specs/openid-client-initiated-backchannel-authentication-core-1_0.html#rfc.section.11).
+    SlowDown,
+    /// The authorization server encountered an unexpected condition that
+    /// prevented it from fulfilling the request.
+    ///
+    /// Defined in
+    /// [Section 4.1.2.1 of RFC 6749](https://tools.ietf.org/html/rfc6749#section-4.1.2.1).
+    ServerError,
+    /// The authorization server is currently unable to handle the request due
+    /// to a temporary overloading or maintenance of the server.
+    ///
+    /// Defined in
+    /// [Section 4.1.2.1 of RFC 6749](https://tools.ietf.org/html/rfc6749#section-4.1.2.1).
+    TemporarilyUnavailable,
+    /// The resource owner or authorization server denied the request.
+    ///
+    /// Defined in
+    /// [Section 4.1.2.1 of RFC 6749](https://tools.ietf.org/html/rfc6749#section-4.1.2.1).
+    AccessDenied,
+    /// The device code or `auth_req_id` has expired, and the flow has to be
+    /// restarted.
+    ///
+    /// Defined in [Section 3.5 of RFC 8628](https://tools.ietf.org/html/rfc8628#section-3.5).
+    ExpiredToken,
+    /// The access token provided is expired, revoked, malformed, or invalid for other reasons.
+    ///
+    /// Defined in [Section 3.1 of RFC 6750](https://tools.ietf.org/html/rfc6750#section-3.1).
+    InvalidToken,
+    /// The request requires higher privileges than provided by the access token.
+    ///
+    /// Defined in [Section 3.1 of RFC 6750](https://tools.ietf.org/html/rfc6750#section-3.1).
+    InsufficientScope,
+    /// The DPoP proof provided with the request is invalid.
+    ///
+    /// Defined in [Section 5 of RFC 9449](https://tools.ietf.org/html/rfc9449#section-5).
+    InvalidDpopProof,
+    /// The server requires a nonce in the DPoP proof, which is provided in the
+    /// `DPoP-Nonce` header of the response.
+    ///
+    /// Defined in [Section 8 of RFC 9449](https://tools.ietf.org/html/rfc9449#section-8).
+    UseDpopNonce,
+    /// The requested resource is invalid, missing, unknown, or malformed.
+    ///
+    /// Defined in [Section 2 of RFC 8707](https://tools.ietf.org/html/rfc8707#section-2).
+    InvalidTarget,
+    /// The authorization server does not support the revocation or exchange of
+    /// the presented token type.
+    ///
+    /// Defined in [Section 2.2.1 of RFC 7009](https://tools.ietf.org/html/rfc7009#section-2.2.1).
+    UnsupportedTokenType,
+    /// The value of one of the client metadata fields is invalid.
+    ///
+    /// Defined in [Section 3.2.2 of RFC 7591](https://tools.ietf.org/html/rfc7591#section-3.2.2).
+    InvalidClientMetadata,
+    /// The authorization server requires some form of end-user interaction to proceed.
+    ///
+    /// Defined in
+    /// [Section 3.1.2.6 of OpenID Connect Core](https://openid.net/specs/openid-connect-core-1_0.html#AuthError).
+    InteractionRequired,
+    /// The authorization server requires end-user authentication.
+    ///
+    /// Defined in
+    /// [Section 3.1.2.6 of OpenID Connect Core](https://openid.net/specs/openid-connect-core-1_0.html#AuthError).
+    LoginRequired,
+    /// The authorization server requires end-user consent.
+    ///
+    /// Defined in
+    /// [Section 3.1.2.6 of OpenID Connect Core](https://openid.net/specs/openid-connect-core-1_0.html#AuthError).
+    ConsentRequired,
     /// Other error type.
     Other(String),
 }
 
-impl fmt::Display for ErrorField {
-    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+impl ErrorField {
+    /// Get the error code as it is represented in an error response.
+    pub fn as_str(&self) -> &str {
         use self::ErrorField::*;
 
         match *self {
-            InvalidRequest => "invalid_request".fmt(fmt),
-            InvalidClient => "invalid_client".fmt(fmt),
-            InvalidGrant => "invalid_grant".fmt(fmt),
-            UnauthorizedClient => "unauthorized_client".fmt(fmt),
-            UnsupportedGrantType => "unsupported_grant_type".fmt(fmt),
-            InvalidScope => "invalid_scope".fmt(fmt),
-            Other(ref value) => value.fmt(fmt),
+            InvalidRequest => "invalid_request",
+            InvalidClient => "invalid_client",
+            InvalidGrant => "invalid_grant",
+            UnauthorizedClient => "unauthorized_client",
+            UnsupportedGrantType => "unsupported_grant_type",
+            InvalidScope => "invalid_scope",
+            AuthorizationPending => "authorization_pending",
+            SlowDown => "slow_down",
+            ServerError => "server_error",
+            TemporarilyUnavailable => "temporarily_unavailable",
+            AccessDenied => "access_denied",
+            ExpiredToken => "expired_token",
+            InvalidToken => "invalid_token",
+            InsufficientScope => "insufficient_scope",
+            InvalidDpopProof => "invalid_dpop_proof",
+            UseDpopNonce => "use_dpop_nonce",
+            InvalidTarget => "invalid_target",
+            UnsupportedTokenType => "unsupported_token_type",
+            InvalidClientMetadata => "invalid_client_metadata",
+            InteractionRequired => "interaction_required",
+            LoginRequired => "login_required",
+            ConsentRequired => "consent_required",
+            Other(ref value) => value,
+        }
+    }
+
+    /// Construct an error field from an error code.
+    fn from_code(code: String) -> Self {
+        use self::ErrorField::*;
+
+        match code.as_str() {
+            "invalid_request" => InvalidRequest,
+            "invalid_client" => InvalidClient,
+            "invalid_grant" => InvalidGrant,
+            "unauthorized_client" => UnauthorizedClient,
+            "unsupported_grant_type" => UnsupportedGrantType,
+            "invalid_scope" => InvalidScope,
+            "authorization_pending" => AuthorizationPending,
+            "slow_down" => SlowDown,
+            "server_error" => ServerError,
+            "temporarily_unavailable" => TemporarilyUnavailable,
+            "access_denied" => AccessDenied,
+            "expired_token" => ExpiredToken,
+            "invalid_token" => InvalidToken,
+            "insufficient_scope" => InsufficientScope,
+            "invalid_dpop_proof" => InvalidDpopProof,
+            "use_dpop_nonce" => UseDpopNonce,
+            "invalid_target" => InvalidTarget,
+            "unsupported_token_type" => UnsupportedTokenType,
+            "invalid_client_metadata" => InvalidClientMetadata,
+            "interaction_required" => InteractionRequired,
+            "login_required" => LoginRequired,
+            "consent_required" => ConsentRequired,
+            _ => Other(code),
         }
     }
 }
 
+impl fmt::Display for ErrorField {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_str().fmt(fmt)
+    }
+}
+
+impl serde::Serialize for ErrorField {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.as_str().serialize(serializer)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for ErrorField {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Ok(ErrorField::from_code(String::deserialize(deserializer)?))
+    }
+}
+
 /// Error response returned by server after requesting an access token.
 ///
 /// The fields in this structure are defined in
@@ -1111,6 +3045,151 @@ impl fmt::Display for ErrorResponse {
 
 impl Error for ErrorResponse {}
 
+/// A violation of the [OAuth 2.1] requirements enforced when a client is in
+/// [strict mode][Client::set_strict].
+///
+/// [OAuth 2.1]: https://datatracker.ietf.org/doc/html/draft-ietf-oauth-v2-1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum StrictViolation {
+    /// The Implicit Grant was used.
+    ImplicitGrant,
+    /// The Resource Owner Password Credentials Grant was used.
+    PasswordGrant,
+    /// An authorization code was requested without a PKCE challenge using
+    /// the `S256` method, or exchanged without a PKCE `code_verifier`.
+    MissingPkce,
+    /// An authorization code was exchanged without a configured redirect URL.
+    MissingRedirectUrl,
+    /// The configured redirect URL contains a fragment.
+    RedirectUrlFragment,
+    /// An access token was sent as a query parameter, either through
+    /// [`Bearer::apply_query`] or as part of the URL of a token request.
+    BearerTokenInQuery,
+}
+
+impl fmt::Display for StrictViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            StrictViolation::ImplicitGrant => "implicit grant is not permitted".fmt(f),
+            StrictViolation::PasswordGrant => "password grant is not permitted".fmt(f),
+            StrictViolation::MissingPkce => "authorization code grant requires PKCE".fmt(f),
+            StrictViolation::MissingRedirectUrl => {
+                "authorization code exchange requires a redirect URL".fmt(f)
+            }
+            StrictViolation::RedirectUrlFragment => {
+                "redirect URL must not contain a fragment".fmt(f)
+            }
+            StrictViolation::BearerTokenInQuery => {
+                "access token must not be sent in the query".fmt(f)
+            }
+        }
+    }
+}
+
+impl Error for StrictViolation {}
+
+/// Error encountered while parsing the redirect from the authorization
+/// endpoint through [`Client::parse_callback`].
+pub struct CallbackError {
+    kind: CallbackErrorKind,
+}
+
+impl CallbackError {
+    /// The error response returned by the authorization server if available.
+    pub fn error_response(&self) -> Option<&ErrorResponse> {
+        match self.kind {
+            CallbackErrorKind::ErrorResponse { ref error } => Some(error),
+            _ => None,
+        }
+    }
+
+    /// Test if the error was caused by the `state` parameter not matching
+    /// the expected state, which might indicate a forged request.
+    pub fn is_state_mismatch(&self) -> bool {
+        matches!(self.kind, CallbackErrorKind::StateMismatch)
+    }
+}
+
+impl From<CallbackErrorKind> for CallbackError {
+    #[inline]
+    fn from(kind: CallbackErrorKind) -> Self {
+        Self { kind }
+    }
+}
+
+impl Error for CallbackError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self.kind {
+            CallbackErrorKind::ErrorResponse { ref error } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for CallbackError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.kind.fmt(f)
+    }
+}
+
+impl fmt::Debug for CallbackError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.kind.fmt(f)
+    }
+}
+
+#[derive(Debug)]
+enum CallbackErrorKind {
+    /// The authorization server redirected with an error.
+    ErrorResponse {
+        /// The error returned.
+        error: ErrorResponse,
+    },
+    /// The callback is missing the `code` parameter.
+    MissingCode,
+    /// The callback is missing the `state` parameter.
+    MissingState,
+    /// The `state` parameter doesn't match the expected state.
+    StateMismatch,
+    /// The URL of the current page is not available.
+    #[cfg_attr(not(feature = "wasm"), allow(unused))]
+    NoCurrentUrl,
+}
+
+impl fmt::Display for CallbackErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            CallbackErrorKind::ErrorResponse { .. } => {
+                "authorization resulted in error response".fmt(f)
+            }
+            CallbackErrorKind::MissingCode => "callback is missing the `code` parameter".fmt(f),
+            CallbackErrorKind::MissingState => "callback is missing the `state` parameter".fmt(f),
+            CallbackErrorKind::StateMismatch => "callback `state` parameter does not match".fmt(f),
+            CallbackErrorKind::NoCurrentUrl => "url of the current page is not available".fmt(f),
+        }
+    }
+}
+
+/// Error raised when a request requires an endpoint which hasn't been
+/// configured on the [`Client`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingEndpoint {
+    name: &'static str,
+}
+
+impl Error for MissingEndpoint {}
+
+impl fmt::Display for MissingEndpoint {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} endpoint is not configured", self.name)
+    }
+}
+
 /// Errors when creating new clients.
 pub struct NewClientError {
     kind: NewClientErrorKind,
@@ -1169,12 +3248,17 @@ impl From<reqwest::Error> for NewClientError {
 /// Error encountered while requesting access token.
 pub struct ExecuteError {
     kind: ExecuteErrorKind,
+    /// Headers of the response, if one was received.
+    headers: Option<Box<HeaderMap>>,
 }
 
 impl From<ExecuteErrorKind> for ExecuteError {
     #[inline]
     fn from(kind: ExecuteErrorKind) -> Self {
-        Self { kind }
+        Self {
+            kind,
+            headers: None,
+        }
     }
 }
 
@@ -1186,9 +3270,30 @@ impl Error for ExecuteError {
             ExecuteErrorKind::SendError { ref error } => Some(error),
             #[cfg(feature = "reqwest")]
             ExecuteErrorKind::BytesError { ref error } => Some(error),
+            #[cfg(feature = "hyper")]
+            ExecuteErrorKind::HyperRequestError { ref error } => Some(error),
+            #[cfg(feature = "hyper")]
+            ExecuteErrorKind::HyperSendError { ref error } => Some(error),
+            #[cfg(feature = "hyper")]
+            ExecuteErrorKind::HyperBytesError { ref error } => Some(error),
+            #[cfg(feature = "ureq")]
+            ExecuteErrorKind::UreqSendError { ref error } => Some(error),
+            #[cfg(feature = "ureq")]
+            ExecuteErrorKind::UreqBytesError { ref error } => Some(error),
+            #[cfg(feature = "wasm")]
+            ExecuteErrorKind::FetchError { ref error } => Some(error),
+            #[cfg(feature = "wasm")]
+            ExecuteErrorKind::FetchBytesError { ref error } => Some(error),
+            #[cfg(feature = "recording")]
+            ExecuteErrorKind::NoReplay => None,
             ExecuteErrorKind::BadResponse { ref error, .. } => Some(error),
             ExecuteErrorKind::ErrorResponse { ref error, .. } => Some(error),
             ExecuteErrorKind::EmptyResponse { .. } => None,
+            ExecuteErrorKind::Strict { ref violation } => Some(violation),
+            ExecuteErrorKind::Timeout => None,
+            ExecuteErrorKind::BackchannelExpired => None,
+            ExecuteErrorKind::AuthorizationRequired => None,
+            ExecuteErrorKind::InvalidAccessToken { ref error } => Some(error),
         }
     }
 }
@@ -1219,9 +3324,46 @@ enum ExecuteErrorKind {
         /// Original request error.
         error: reqwest::Error,
     },
+    #[cfg(feature = "hyper")]
+    HyperRequestError {
+        /// Original request error.
+        error: http::Error,
+    },
+    #[cfg(feature = "hyper")]
+    HyperSendError {
+        /// Original request error.
+        error: hyper_util::client::legacy::Error,
+    },
+    #[cfg(feature = "hyper")]
+    HyperBytesError {
+        /// Original request error.
+        error: hyper::Error,
+    },
+    #[cfg(feature = "ureq")]
+    UreqSendError {
+        /// Original request error.
+        error: ureq::Error,
+    },
+    #[cfg(feature = "ureq")]
+    UreqBytesError {
+        /// Original request error.
+        error: ureq::Error,
+    },
+    #[cfg(feature = "wasm")]
+    FetchError {
+        /// Original request error.
+        error: wasm::JsError,
+    },
+    #[cfg(feature = "wasm")]
+    FetchBytesError {
+        /// Original request error.
+        error: wasm::JsError,
+    },
+    /// No response was queued on the recorder.
+    #[cfg(feature = "recording")]
+    NoReplay,
     /// Failed to parse server response. Parse errors may occur while parsing either successful
     /// or error responses.
-    #[cfg_attr(not(any(feature = "reqwest")), allow(unused))]
     BadResponse {
         /// The status code associated with the response.
         status: StatusCode,
@@ -1230,9 +3372,9 @@ enum ExecuteErrorKind {
         /// Deserialization error.
         error: serde_json::error::Error,
     },
-    /// Response with non-successful status code and a body that could be
-    /// successfully deserialized as an [ErrorResponse].
-    #[cfg_attr(not(any(feature = "reqwest")), allow(unused))]
+    /// Response with non-successful status code, or a successful status code
+    /// and an `error` member, and a body that could be successfully
+    /// deserialized as an [ErrorResponse].
     ErrorResponse {
         /// The status code associated with the response.
         status: StatusCode,
@@ -1240,11 +3382,85 @@ enum ExecuteErrorKind {
         error: ErrorResponse,
     },
     /// Server response was empty.
-    #[cfg_attr(not(any(feature = "reqwest")), allow(unused))]
     EmptyResponse {
         /// The status code associated with the empty response.
         status: StatusCode,
     },
+    /// The request was refused before being sent since it violates strict
+    /// mode.
+    Strict {
+        /// The violation that was detected.
+        violation: StrictViolation,
+    },
+    /// The request timed out.
+    ///
+    /// This is never raised when replaying recorded responses.
+    #[cfg_attr(
+        not(any(
+            feature = "reqwest",
+            feature = "hyper",
+            feature = "ureq",
+            feature = "wasm"
+        )),
+        allow(dead_code)
+    )]
+    Timeout,
+    /// The backchannel authentication request expired while polling.
+    BackchannelExpired,
+    /// The refresh token of a token manager has been discarded.
+    #[cfg_attr(not(all(feature = "std", feature = "reqwest")), allow(dead_code))]
+    AuthorizationRequired,
+    /// The access token received can't be used in an `Authorization` header.
+    #[cfg_attr(
+        not(any(feature = "tower", feature = "reqwest-middleware")),
+        allow(dead_code)
+    )]
+    InvalidAccessToken {
+        /// Why the access token can't be used.
+        error: BearerError,
+    },
+}
+
+#[cfg(feature = "ureq")]
+impl ExecuteErrorKind {
+    /// Classify an error encountered while sending a request with ureq.
+    fn ureq_send_error(error: ureq::Error) -> Self {
+        if let ureq::Error::Timeout(..) = error {
+            return ExecuteErrorKind::Timeout;
+        }
+
+        ExecuteErrorKind::UreqSendError { error }
+    }
+
+    /// Classify an error encountered while reading a response with ureq.
+    fn ureq_bytes_error(error: ureq::Error) -> Self {
+        if let ureq::Error::Timeout(..) = error {
+            return ExecuteErrorKind::Timeout;
+        }
+
+        ExecuteErrorKind::UreqBytesError { error }
+    }
+}
+
+#[cfg(feature = "reqwest")]
+impl ExecuteErrorKind {
+    /// Classify an error encountered while sending a request.
+    fn send_error(error: reqwest::Error) -> Self {
+        if error.is_timeout() {
+            return ExecuteErrorKind::Timeout;
+        }
+
+        ExecuteErrorKind::SendError { error }
+    }
+
+    /// Classify an error encountered while reading a response.
+    fn bytes_error(error: reqwest::Error) -> Self {
+        if error.is_timeout() {
+            return ExecuteErrorKind::Timeout;
+        }
+
+        ExecuteErrorKind::BytesError { error }
+    }
 }
 
 impl fmt::Display for ExecuteErrorKind {
@@ -1254,6 +3470,22 @@ impl fmt::Display for ExecuteErrorKind {
             ExecuteErrorKind::SendError { .. } => "error sending request".fmt(f),
             #[cfg(feature = "reqwest")]
             ExecuteErrorKind::BytesError { .. } => "error reading response bytes".fmt(f),
+            #[cfg(feature = "hyper")]
+            ExecuteErrorKind::HyperRequestError { .. } => "error sending request".fmt(f),
+            #[cfg(feature = "hyper")]
+            ExecuteErrorKind::HyperSendError { .. } => "error sending request".fmt(f),
+            #[cfg(feature = "hyper")]
+            ExecuteErrorKind::HyperBytesError { .. } => "error reading response bytes".fmt(f),
+            #[cfg(feature = "ureq")]
+            ExecuteErrorKind::UreqSendError { .. } => "error sending request".fmt(f),
+            #[cfg(feature = "ureq")]
+            ExecuteErrorKind::UreqBytesError { .. } => "error reading response bytes".fmt(f),
+            #[cfg(feature = "wasm")]
+            ExecuteErrorKind::FetchError { .. } => "error sending request".fmt(f),
+            #[cfg(feature = "wasm")]
+            ExecuteErrorKind::FetchBytesError { .. } => "error reading response bytes".fmt(f),
+            #[cfg(feature = "recording")]
+            ExecuteErrorKind::NoReplay => "no response queued for replay".fmt(f),
             ExecuteErrorKind::BadResponse { status, .. } => {
                 write!(f, "malformed server response: {status}")
             }
@@ -1263,6 +3495,15 @@ impl fmt::Display for ExecuteErrorKind {
             ExecuteErrorKind::EmptyResponse { status } => {
                 write!(f, "request resulted in empty response: {status}")
             }
+            ExecuteErrorKind::Strict { .. } => "request refused in strict mode".fmt(f),
+            ExecuteErrorKind::Timeout => "request timed out".fmt(f),
+            ExecuteErrorKind::BackchannelExpired => {
+                "backchannel authentication request expired".fmt(f)
+            }
+            ExecuteErrorKind::AuthorizationRequired => {
+                "refresh token is no longer available, authorization is required".fmt(f)
+            }
+            ExecuteErrorKind::InvalidAccessToken { .. } => "invalid access token".fmt(f),
         }
     }
 }
@@ -1276,12 +3517,49 @@ impl ExecuteError {
             ExecuteErrorKind::SendError { ref error } => error.status(),
             #[cfg(feature = "reqwest")]
             ExecuteErrorKind::BytesError { ref error } => error.status(),
+            #[cfg(feature = "hyper")]
+            ExecuteErrorKind::HyperRequestError { .. } => None,
+            #[cfg(feature = "hyper")]
+            ExecuteErrorKind::HyperSendError { .. } => None,
+            #[cfg(feature = "hyper")]
+            ExecuteErrorKind::HyperBytesError { .. } => None,
+            #[cfg(feature = "ureq")]
+            ExecuteErrorKind::UreqSendError { .. } => None,
+            #[cfg(feature = "ureq")]
+            ExecuteErrorKind::UreqBytesError { .. } => None,
+            #[cfg(feature = "wasm")]
+            ExecuteErrorKind::FetchError { .. } => None,
+            #[cfg(feature = "wasm")]
+            ExecuteErrorKind::FetchBytesError { .. } => None,
+            #[cfg(feature = "recording")]
+            ExecuteErrorKind::NoReplay => None,
             ExecuteErrorKind::BadResponse { status, .. } => Some(status),
             ExecuteErrorKind::ErrorResponse { status, .. } => Some(status),
             ExecuteErrorKind::EmptyResponse { status, .. } => Some(status),
+            ExecuteErrorKind::Strict { .. } => None,
+            ExecuteErrorKind::Timeout => None,
+            ExecuteErrorKind::BackchannelExpired => None,
+            ExecuteErrorKind::AuthorizationRequired => None,
+            ExecuteErrorKind::InvalidAccessToken { .. } => None,
         }
     }
 
+    /// The headers of the response if available.
+    pub fn headers(&self) -> Option<&HeaderMap> {
+        self.headers.as_deref()
+    }
+
+    /// The delay indicated by the `Retry-After` header of the response if
+    /// available.
+    ///
+    /// Only the delay-seconds form of the header is supported, so `None` is
+    /// returned if the header uses the HTTP-date form.
+    pub fn retry_after(&self) -> Option<Duration> {
+        let value = self.headers()?.get(http::header::RETRY_AFTER)?;
+        let secs = value.to_str().ok()?.trim().parse::<u64>().ok()?;
+        Some(Duration::from_secs(secs))
+    }
+
     /// The original response body if available.
     pub fn body(&self) -> Option<&Bytes> {
         match self.kind {
@@ -1289,8 +3567,162 @@ impl ExecuteError {
             _ => None,
         }
     }
+
+    /// The kind of error.
+    pub fn kind(&self) -> ErrorKind {
+        match self.kind {
+            #[cfg(feature = "reqwest")]
+            ExecuteErrorKind::SendError { .. } => ErrorKind::Transport,
+            #[cfg(feature = "reqwest")]
+            ExecuteErrorKind::BytesError { .. } => ErrorKind::Transport,
+            #[cfg(feature = "hyper")]
+            ExecuteErrorKind::HyperRequestError { .. } => ErrorKind::Transport,
+            #[cfg(feature = "hyper")]
+            ExecuteErrorKind::HyperSendError { .. } => ErrorKind::Transport,
+            #[cfg(feature = "hyper")]
+            ExecuteErrorKind::HyperBytesError { .. } => ErrorKind::Transport,
+            #[cfg(feature = "ureq")]
+            ExecuteErrorKind::UreqSendError { .. } => ErrorKind::Transport,
+            #[cfg(feature = "ureq")]
+            ExecuteErrorKind::UreqBytesError { .. } => ErrorKind::Transport,
+            #[cfg(feature = "wasm")]
+            ExecuteErrorKind::FetchError { .. } => ErrorKind::Transport,
+            #[cfg(feature = "wasm")]
+            ExecuteErrorKind::FetchBytesError { .. } => ErrorKind::Transport,
+            #[cfg(feature = "recording")]
+            ExecuteErrorKind::NoReplay => ErrorKind::Transport,
+            ExecuteErrorKind::BadResponse { .. } => ErrorKind::BadResponse,
+            ExecuteErrorKind::ErrorResponse { .. } => ErrorKind::ErrorResponse,
+            ExecuteErrorKind::EmptyResponse { .. } => ErrorKind::EmptyResponse,
+            ExecuteErrorKind::Strict { .. } => ErrorKind::Strict,
+            ExecuteErrorKind::Timeout => ErrorKind::Timeout,
+            ExecuteErrorKind::BackchannelExpired => ErrorKind::Expired,
+            ExecuteErrorKind::AuthorizationRequired => ErrorKind::AuthorizationRequired,
+            ExecuteErrorKind::InvalidAccessToken { .. } => ErrorKind::BadResponse,
+        }
+    }
+
+    /// The error response returned by the server if available.
+    pub fn error_response(&self) -> Option<&ErrorResponse> {
+        match self.kind {
+            ExecuteErrorKind::ErrorResponse { ref error, .. } => Some(error),
+            _ => None,
+        }
+    }
+
+    /// The strict mode violation which caused the request to be refused if
+    /// available.
+    pub fn strict_violation(&self) -> Option<StrictViolation> {
+        match self.kind {
+            ExecuteErrorKind::Strict { violation } => Some(violation),
+            _ => None,
+        }
+    }
+
+    /// Test if the error was caused by the transport, such as a failure to
+    /// connect or to read the response.
+    pub fn is_transport(&self) -> bool {
+        self.kind() == ErrorKind::Transport
+    }
+
+    /// Test if the error was caused by a failure to connect to the server, in
+    /// which case the request was never sent.
+    pub fn is_connect(&self) -> bool {
+        match self.kind {
+            #[cfg(feature = "reqwest")]
+            ExecuteErrorKind::SendError { ref error } => error.is_connect(),
+            #[cfg(feature = "hyper")]
+            ExecuteErrorKind::HyperSendError { ref error } => error.is_connect(),
+            #[cfg(feature = "ureq")]
+            ExecuteErrorKind::UreqSendError { ref error } => match error {
+                ureq::Error::HostNotFound | ureq::Error::ConnectionFailed => true,
+                ureq::Error::Io(error) => error.kind() == std::io::ErrorKind::ConnectionRefused,
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Test if the error was caused by the request timing out.
+    pub fn is_timeout(&self) -> bool {
+        self.kind() == ErrorKind::Timeout
+    }
+
+    /// Test if the server responded with an [`ErrorField::InvalidGrant`]
+    /// error.
+    ///
+    /// For refresh tokens this typically means that the token has expired or
+    /// been revoked, and that the user has to authorize the client again.
+    pub fn is_invalid_grant(&self) -> bool {
+        self.error_response()
+            .is_some_and(|error| error.error == ErrorField::InvalidGrant)
+    }
+}
+
+/// The kind of an [`ExecuteError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The request could not be sent or the response could not be received.
+    Transport,
+    /// The server responded with a body that could not be deserialized, or
+    /// with an access token that can't be used.
+    BadResponse,
+    /// The server responded with an [`ErrorResponse`], which can be accessed
+    /// through [`ExecuteError::error_response`].
+    ErrorResponse,
+    /// The server responded with an empty body.
+    EmptyResponse,
+    /// The request was refused before being sent since it violates strict
+    /// mode, see [`ExecuteError::strict_violation`].
+    Strict,
+    /// The request timed out, either due to the timeout configured through
+    /// [`Client::set_timeout`] or [`Request::timeout`], or due to a timeout
+    /// configured on the underlying transport.
+    Timeout,
+    /// The backchannel authentication request expired before the user
+    /// approved it while polling through
+    /// [`Client::poll_backchannel_authentication`], so the flow has to be
+    /// started again.
+    Expired,
+    /// A new token can't be requested through a `TokenManager`, since its
+    /// refresh token was rejected or its request was interrupted, so
+    /// authorization has to be requested again.
+    AuthorizationRequired,
 }
 
+mod bearer;
+pub use self::bearer::{Bearer, BearerError};
+
+#[cfg(any(
+    feature = "reqwest",
+    feature = "hyper",
+    feature = "ureq",
+    feature = "wasm",
+    feature = "recording"
+))]
+mod transport;
+
+#[cfg(all(feature = "std", feature = "reqwest"))]
+mod manager;
+#[cfg(all(feature = "std", feature = "reqwest"))]
+pub use self::manager::TokenManager;
+
+#[cfg(feature = "tower")]
+pub mod tower;
+
+#[cfg(feature = "reqwest-middleware")]
+pub mod reqwest_middleware;
+
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+#[cfg(feature = "mock")]
+pub mod mock;
+
+#[cfg(feature = "recording")]
+pub mod recording;
+
 /// Helper methods used by OAuth2 implementations/extensions.
 pub mod helpers {
     use alloc::string::{String, ToString};
@@ -1301,7 +3733,9 @@ pub mod helpers {
 
     /// Serde space-delimited string deserializer for a `Vec<String>`.
     ///
-    /// This function splits a JSON string at each space character into a `Vec<String>` .
+    /// This function splits a JSON string at each space character into a `Vec<String>`,
+    /// ignoring repeated spaces. Consider using [`Scopes`][crate::Scopes] instead if the
+    /// string contains scopes.
     ///
     /// # Example
     ///
@@ -1343,6 +3777,7 @@ pub mod helpers {
         if let Some(space_delimited) = Option::<String>::deserialize(deserializer)? {
             let entries = space_delimited
                 .split(' ')
+                .filter(|s| !s.is_empty())
                 .map(|s| Value::String(s.to_string()))
                 .collect();
             return T::deserialize(Value::Array(entries)).map_err(Error::custom);
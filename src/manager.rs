@@ -0,0 +1,351 @@
+//! Management of access tokens on behalf of a [`Client`].
+
+use core::fmt;
+use core::future::poll_fn;
+use core::mem;
+use core::task::{Poll, Waker};
+use core::time::Duration;
+
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use std::sync::{Mutex, MutexGuard};
+
+use crate::{
+    AccessToken, Client, Clock, ExecuteError, ExecuteErrorKind, IssuedToken, RefreshToken,
+    StandardToken, SystemClock, Token,
+};
+
+/// Keeps track of an access token for a [`Client`], requesting a new one
+/// when it's about to expire.
+///
+/// New tokens are requested using the refresh token of the current token if
+/// one is available, and using the [Client Credentials Grant] otherwise.
+/// Tokens obtained through other grants can be provided through
+/// [`TokenManager::set_token`].
+///
+/// If a refresh fails with [`ErrorField::InvalidGrant`], the refresh token is
+/// discarded. Refresh tokens which were issued in response to a client
+/// credentials request are then replaced by requesting a new token using the
+/// client credentials. Otherwise the error is returned, and authorization
+/// has to be requested again before providing a new token.
+///
+/// If a refresh is cancelled or times out, the server might have rotated the
+/// refresh token without the new one being received, and sending the old one
+/// again could cause the server to revoke the whole grant. The refresh token
+/// is therefore discarded. Refresh tokens which were issued in response to a
+/// client credentials request are then replaced using the client credentials.
+/// Otherwise new tokens are refused with [`ErrorKind::AuthorizationRequired`]
+/// until authorization has been requested again and a new token provided.
+///
+/// The manager is cheap to clone, and clones share the same token. Only one
+/// token request is in flight at a time, which matters when the server
+/// rotates refresh tokens.
+///
+/// [`ErrorField::InvalidGrant`]: crate::ErrorField::InvalidGrant
+/// [`ErrorKind::AuthorizationRequired`]: crate::ErrorKind::AuthorizationRequired
+/// [Client Credentials Grant]: https://tools.ietf.org/html/rfc6749#section-4.4
+#[derive(Clone)]
+pub struct TokenManager {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    client: Client,
+    http: reqwest::Client,
+    state: Mutex<State>,
+}
+
+struct State {
+    token: Option<IssuedToken<StandardToken>>,
+    refresh_token: Option<RefreshToken>,
+    /// Whether the refresh token was issued in response to a client
+    /// credentials request.
+    client_credentials: bool,
+    /// Whether the refresh token was discarded after an interrupted refresh,
+    /// so that authorization has to be requested again.
+    reauthorize: bool,
+    expiry_skew: Duration,
+    clock: Box<dyn Clock + Send>,
+    refreshing: bool,
+    waiters: Vec<Waker>,
+}
+
+impl TokenManager {
+    /// Construct a new token manager which requests tokens for `client`
+    /// using the given reqwest client.
+    pub fn new(client: Client, http: reqwest::Client) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                client,
+                http,
+                state: Mutex::new(State {
+                    token: None,
+                    refresh_token: None,
+                    client_credentials: false,
+                    reauthorize: false,
+                    expiry_skew: Duration::from_secs(30),
+                    clock: Box::new(SystemClock),
+                    refreshing: false,
+                    waiters: Vec::new(),
+                }),
+            }),
+        }
+    }
+
+    /// Set the current token, for example one obtained through the
+    /// [Authorization Code Grant].
+    ///
+    /// [Authorization Code Grant]: https://tools.ietf.org/html/rfc6749#section-4.1
+    pub fn set_token(&self, token: StandardToken) {
+        let mut state = self.lock();
+
+        if token.refresh_token().is_some() {
+            state.client_credentials = false;
+        }
+
+        state.reauthorize = false;
+        state.store(token);
+    }
+
+    /// Set the refresh token used to request new tokens, for example one
+    /// which has been persisted from an earlier session.
+    pub fn set_refresh_token(&self, refresh_token: RefreshToken) {
+        let mut state = self.lock();
+        state.refresh_token = Some(refresh_token);
+        state.client_credentials = false;
+        state.reauthorize = false;
+    }
+
+    /// Set the clock used to determine when tokens expire.
+    ///
+    /// Defaults to [`SystemClock`].
+    pub fn set_clock<C>(&self, clock: C)
+    where
+        C: Clock + Send + 'static,
+    {
+        self.lock().clock = Box::new(clock);
+    }
+
+    /// Configure how long before its expiry a token is replaced.
+    ///
+    /// Defaults to 30 seconds.
+    pub fn set_expiry_skew(&self, expiry_skew: Duration) {
+        self.lock().expiry_skew = expiry_skew;
+    }
+
+    /// Get a valid access token, requesting a new token if necessary.
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`ErrorKind::AuthorizationRequired`] if a refresh was
+    /// interrupted earlier, see [`TokenManager`].
+    ///
+    /// [`ErrorKind::AuthorizationRequired`]: crate::ErrorKind::AuthorizationRequired
+    pub async fn access_token(&self) -> Result<AccessToken, ExecuteError> {
+        let token = self.token_replacing(None).await?;
+        Ok(token.access_token().clone())
+    }
+
+    /// Get a valid token whose access token is different from `rejected`,
+    /// which is used after the server rejected it.
+    pub(crate) async fn token_replacing(
+        &self,
+        rejected: Option<&AccessToken>,
+    ) -> Result<StandardToken, ExecuteError> {
+        let acquired = poll_fn(|cx| {
+            let mut state = self.lock();
+
+            if let Some(token) = state.valid_token(rejected) {
+                return Poll::Ready(Acquired::Token(token));
+            }
+
+            if state.reauthorize {
+                return Poll::Ready(Acquired::Reauthorize);
+            }
+
+            if state.refreshing {
+                state.waiters.push(cx.waker().clone());
+                return Poll::Pending;
+            }
+
+            state.refreshing = true;
+            Poll::Ready(Acquired::Refresh(state.refresh_token.clone()))
+        })
+        .await;
+
+        let refresh_token = match acquired {
+            Acquired::Token(token) => return Ok(token),
+            Acquired::Reauthorize => {
+                return Err(ExecuteError::from(ExecuteErrorKind::AuthorizationRequired));
+            }
+            Acquired::Refresh(refresh_token) => refresh_token,
+        };
+
+        // Release the refresh to other callers even if this future is
+        // dropped before the request completes.
+        let mut guard = RefreshGuard {
+            manager: self,
+            in_flight: refresh_token.as_ref(),
+        };
+
+        let (token, client_credentials) = match &refresh_token {
+            Some(refresh_token) => {
+                let result = self.request(Some(refresh_token)).await;
+                guard.in_flight = None;
+
+                match result {
+                    Err(error) if error.is_invalid_grant() => {
+                        if !self.discard_refresh_token(refresh_token, false) {
+                            return Err(error);
+                        }
+
+                        (self.request(None).await?, true)
+                    }
+                    Err(error) if error.is_timeout() => {
+                        if !self.discard_refresh_token(refresh_token, true) {
+                            return Err(error);
+                        }
+
+                        (self.request(None).await?, true)
+                    }
+                    result => (result?, false),
+                }
+            }
+            None => (self.request(None).await?, true),
+        };
+
+        let mut state = guard.manager.lock();
+
+        if client_credentials && token.refresh_token().is_some() {
+            state.client_credentials = true;
+        }
+
+        state.store(token.clone());
+        Ok(token)
+    }
+
+    /// Request a new token using the given refresh token, or using the client
+    /// credentials if there is none.
+    async fn request(
+        &self,
+        refresh_token: Option<&RefreshToken>,
+    ) -> Result<StandardToken, ExecuteError> {
+        let request = match refresh_token {
+            Some(refresh_token) => self.inner.client.exchange_refresh_token(refresh_token),
+            None => self.inner.client.exchange_client_credentials(),
+        };
+
+        request
+            .with_reqwest_client(&self.inner.http)
+            .execute()
+            .await
+    }
+
+    /// See [`State::discard_refresh_token`].
+    fn discard_refresh_token(&self, rejected: &RefreshToken, interrupted: bool) -> bool {
+        self.lock().discard_refresh_token(rejected, interrupted)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        match self.inner.state.lock() {
+            Ok(state) => state,
+            Err(error) => error.into_inner(),
+        }
+    }
+}
+
+impl fmt::Debug for TokenManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TokenManager")
+            .field("client", &self.inner.client)
+            .finish_non_exhaustive()
+    }
+}
+
+impl State {
+    /// Get the current token if it's still valid and its access token hasn't
+    /// been rejected.
+    fn valid_token(&self, rejected: Option<&AccessToken>) -> Option<StandardToken> {
+        let token = self.token.as_ref()?;
+
+        if token.expires_within(self.expiry_skew, self.clock.now()) {
+            return None;
+        }
+
+        if rejected.is_some_and(|rejected| **rejected == **token.access_token()) {
+            return None;
+        }
+
+        Some(token.token().clone())
+    }
+
+    /// Discard a refresh token which has been rejected by the server, or
+    /// whose refresh was `interrupted`, unless it has been replaced in the
+    /// meantime.
+    ///
+    /// Returns `true` if the refresh token was issued in response to a client
+    /// credentials request, in which case a new token can be requested using
+    /// the client credentials.
+    fn discard_refresh_token(&mut self, rejected: &RefreshToken, interrupted: bool) -> bool {
+        let current = self.refresh_token.as_ref();
+
+        if current.is_none_or(|refresh_token| **refresh_token != **rejected) {
+            return false;
+        }
+
+        self.refresh_token = None;
+        let client_credentials = mem::take(&mut self.client_credentials);
+        self.reauthorize = interrupted && !client_credentials;
+        client_credentials
+    }
+
+    fn store(&mut self, token: StandardToken) {
+        // Servers which don't rotate refresh tokens might not include one in
+        // the response, in which case the old one remains valid.
+        if let Some(refresh_token) = token.refresh_token() {
+            self.refresh_token = Some(refresh_token.clone());
+        }
+
+        self.token = Some(IssuedToken::new(token, self.clock.now()));
+    }
+}
+
+/// What a caller of [`TokenManager::token_replacing`] acquired.
+enum Acquired {
+    /// A valid token.
+    Token(StandardToken),
+    /// The responsibility to request a new token, using the refresh token if
+    /// there is one.
+    Refresh(Option<RefreshToken>),
+    /// Nothing, since authorization has to be requested again.
+    Reauthorize,
+}
+
+/// Marks a refresh as completed when dropped, waking up any waiting callers.
+struct RefreshGuard<'a> {
+    manager: &'a TokenManager,
+    /// The refresh token of a request which is in flight, which is discarded
+    /// if the guard is dropped before the request completes.
+    in_flight: Option<&'a RefreshToken>,
+}
+
+impl Drop for RefreshGuard<'_> {
+    fn drop(&mut self) {
+        let waiters = {
+            let mut state = self.manager.lock();
+
+            if let Some(refresh_token) = self.in_flight {
+                state.discard_refresh_token(refresh_token, true);
+            }
+
+            state.refreshing = false;
+            mem::take(&mut state.waiters)
+        };
+
+        for waker in waiters {
+            waker.wake();
+        }
+    }
+}
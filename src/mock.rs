@@ -0,0 +1,889 @@
+//! An in-process mock authorization server for testing code built on
+//! [`Client`].
+//!
+//! [`MockServer`] listens on a random port on localhost and implements the
+//! following endpoints:
+//! * The [authorization endpoint], supporting the `code` and `token` response
+//!   types, including PKCE.
+//! * The [token endpoint], supporting every grant implemented by [`Client`].
+//! * The [revocation endpoint] from RFC 7009.
+//! * The [introspection endpoint] from RFC 7662.
+//! * The [backchannel authentication endpoint] from CIBA, which approves
+//!   every request immediately.
+//!
+//! Every request is recorded and can be inspected through
+//! [`MockServer::requests`]. Responses can be scripted through
+//! [`MockServer::enqueue`], which takes precedence over the built-in
+//! behavior.
+//!
+//! ```
+//! use oauth2::mock::{Endpoint, MockServer};
+//! use oauth2::{StandardToken, Token};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let server = MockServer::start().await?;
+//! server.register_client("client_id", Some("client_secret"));
+//!
+//! let mut client = server.client("client_id");
+//! client.set_client_secret("client_secret");
+//! client.add_scope("read");
+//!
+//! let http = reqwest::Client::new();
+//!
+//! let token = client
+//!     .exchange_client_credentials()
+//!     .with_reqwest_client(&http)
+//!     .execute::<StandardToken>()
+//!     .await?;
+//!
+//! assert_eq!(&**token.access_token(), "mock-access-token-1");
+//!
+//! let requests = server.requests_to(Endpoint::Token);
+//! assert_eq!(requests.len(), 1);
+//! assert_eq!(requests[0].form("grant_type"), Some("client_credentials"));
+//! assert_eq!(requests[0].form("scope"), Some("read"));
+//! # Ok(()) }
+//! ```
+//!
+//! [authorization endpoint]: https://tools.ietf.org/html/rfc6749#section-3.1
+//! [token endpoint]: https://tools.ietf.org/html/rfc6749#section-3.2
+//! [revocation endpoint]: https://tools.ietf.org/html/rfc7009#section-2
+//! [introspection endpoint]: https://tools.ietf.org/html/rfc7662#section-2
+//! [backchannel authentication endpoint]: https://openid.net/specs/openid-client-initiated-backchannel-authentication-core-1_0.html#auth_backchannel_endpoint
+
+use core::fmt;
+use core::time::Duration;
+
+use alloc::borrow::ToOwned;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use std::collections::HashMap;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::{Mutex, MutexGuard};
+
+use base64::prelude::{Engine as _, BASE64_STANDARD, BASE64_URL_SAFE_NO_PAD};
+use bytes::Bytes;
+use http::header::{
+    HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, CONTENT_TYPE, LOCATION, WWW_AUTHENTICATE,
+};
+use http::{Method, StatusCode};
+use http_body_util::{BodyExt, Full};
+use hyper::body::Incoming;
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper_util::rt::TokioIo;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+use url::Url;
+
+use crate::{Client, ErrorField};
+
+/// How long issued access tokens claim to be valid for.
+const EXPIRES_IN: u64 = 3600;
+
+/// An endpoint served by a [`MockServer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Endpoint {
+    /// The authorization endpoint, served at `/authorize`.
+    Authorize,
+    /// The token endpoint, served at `/token`.
+    Token,
+    /// The token revocation endpoint, served at `/revoke`.
+    Revocation,
+    /// The token introspection endpoint, served at `/introspect`.
+    Introspection,
+    /// The backchannel authentication endpoint, served at `/backchannel`.
+    BackchannelAuthentication,
+}
+
+impl Endpoint {
+    /// The path the endpoint is served at.
+    pub fn path(self) -> &'static str {
+        match self {
+            Endpoint::Authorize => "/authorize",
+            Endpoint::Token => "/token",
+            Endpoint::Revocation => "/revoke",
+            Endpoint::Introspection => "/introspect",
+            Endpoint::BackchannelAuthentication => "/backchannel",
+        }
+    }
+
+    fn from_path(path: &str) -> Option<Self> {
+        [
+            Endpoint::Authorize,
+            Endpoint::Token,
+            Endpoint::Revocation,
+            Endpoint::Introspection,
+            Endpoint::BackchannelAuthentication,
+        ]
+        .into_iter()
+        .find(|endpoint| endpoint.path() == path)
+    }
+}
+
+/// A scripted response, see [`MockServer::enqueue`].
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+    delay: Option<Duration>,
+}
+
+impl MockResponse {
+    /// Construct an empty response with the given status code.
+    pub fn new(status: StatusCode) -> Self {
+        Self {
+            status,
+            headers: HeaderMap::new(),
+            body: Bytes::new(),
+            delay: None,
+        }
+    }
+
+    /// Construct a response with a JSON body.
+    pub fn json(status: StatusCode, body: &serde_json::Value) -> Self {
+        Self::new(status)
+            .header(CONTENT_TYPE, HeaderValue::from_static("application/json"))
+            .body(body.to_string())
+    }
+
+    /// Construct an error response as described in
+    /// [Section 5.2 of RFC 6749](https://tools.ietf.org/html/rfc6749#section-5.2).
+    pub fn error(status: StatusCode, error: ErrorField) -> Self {
+        Self::json(status, &json!({ "error": error.as_str() }))
+    }
+
+    /// Add a header to the response.
+    pub fn header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.append(name, value);
+        self
+    }
+
+    /// Set the body of the response.
+    pub fn body(mut self, body: impl Into<Bytes>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /// Delay sending the response, for example to test how clients deal with
+    /// requests which time out or are cancelled.
+    ///
+    /// The request is recorded before the delay.
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+
+    fn into_response(self) -> http::Response<Full<Bytes>> {
+        let mut response = http::Response::new(Full::new(self.body));
+        *response.status_mut() = self.status;
+        *response.headers_mut() = self.headers;
+        response
+    }
+}
+
+/// A request received by a [`MockServer`].
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    endpoint: Option<Endpoint>,
+    method: Method,
+    path: String,
+    headers: HeaderMap,
+    body: Bytes,
+    query: Vec<(String, String)>,
+    form: Vec<(String, String)>,
+}
+
+impl RecordedRequest {
+    /// The endpoint the request was sent to, or `None` if the path didn't
+    /// match any endpoint.
+    pub fn endpoint(&self) -> Option<Endpoint> {
+        self.endpoint
+    }
+
+    /// The method of the request.
+    pub fn method(&self) -> &Method {
+        &self.method
+    }
+
+    /// The path of the request.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// The headers of the request.
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// The raw body of the request.
+    pub fn body(&self) -> &Bytes {
+        &self.body
+    }
+
+    /// Get the first query parameter with the given name.
+    pub fn query(&self, name: &str) -> Option<&str> {
+        find(&self.query, name)
+    }
+
+    /// Get the first parameter with the given name from a form encoded body.
+    pub fn form(&self, name: &str) -> Option<&str> {
+        find(&self.form, name)
+    }
+
+    /// Decode the client credentials sent in a `Basic` `Authorization`
+    /// header, as described in
+    /// [Section 2.3.1 of RFC 6749](https://tools.ietf.org/html/rfc6749#section-2.3.1).
+    pub fn basic_auth(&self) -> Option<(String, String)> {
+        let value = self.headers.get(AUTHORIZATION)?.to_str().ok()?;
+        let (scheme, credentials) = value.split_once(' ')?;
+
+        if !scheme.eq_ignore_ascii_case("basic") {
+            return None;
+        }
+
+        let credentials = BASE64_STANDARD.decode(credentials.trim()).ok()?;
+        let credentials = String::from_utf8(credentials).ok()?;
+        let (id, secret) = credentials.split_once(':')?;
+        Some((form_decode(id), form_decode(secret)))
+    }
+
+    /// The client credentials of the request, either from a `Basic`
+    /// `Authorization` header or from the `client_id` and `client_secret`
+    /// form parameters.
+    ///
+    /// An empty `Basic` password is treated as no secret, since that is how
+    /// clients without a secret are sent using `Basic` authentication.
+    fn client_credentials(&self) -> Option<(String, Option<String>)> {
+        if let Some((id, secret)) = self.basic_auth() {
+            return Some((id, Some(secret).filter(|secret| !secret.is_empty())));
+        }
+
+        let id = self.form("client_id")?;
+        Some((
+            id.to_owned(),
+            self.form("client_secret").map(ToOwned::to_owned),
+        ))
+    }
+
+    /// Parameters of the request, taken from the query for `GET` requests and
+    /// from the body otherwise.
+    fn param(&self, name: &str) -> Option<&str> {
+        if self.method == Method::GET {
+            self.query(name)
+        } else {
+            self.form(name)
+        }
+    }
+}
+
+/// A mock authorization server running on localhost.
+///
+/// The server is shut down when this is dropped. It must be started from
+/// within a tokio runtime.
+pub struct MockServer {
+    addr: SocketAddr,
+    shared: Arc<Mutex<Shared>>,
+    task: JoinHandle<()>,
+}
+
+impl MockServer {
+    /// Start a new server listening on a random port on localhost.
+    pub async fn start() -> io::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let shared = Arc::new(Mutex::new(Shared::default()));
+
+        let task = tokio::spawn(accept(listener, shared.clone()));
+
+        Ok(Self { addr, shared, task })
+    }
+
+    /// The address the server is listening on.
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// The URL of the given endpoint.
+    pub fn url(&self, endpoint: Endpoint) -> Url {
+        let url = format!("http://{}{}", self.addr, endpoint.path());
+        Url::parse(&url).expect("endpoint url should be valid")
+    }
+
+    /// Construct a client which uses the endpoints of this server.
+    pub fn client(&self, client_id: &str) -> Client {
+        let mut client = Client::new(
+            client_id,
+            self.url(Endpoint::Authorize),
+            self.url(Endpoint::Token),
+        );
+
+        client.set_backchannel_authentication_url(self.url(Endpoint::BackchannelAuthentication));
+        client
+    }
+
+    /// Register a client, optionally with a secret.
+    ///
+    /// Once a client has been registered, requests which require client
+    /// authentication are rejected with `invalid_client` unless they
+    /// identify a registered client with a matching secret. Clients
+    /// registered without a secret authenticate using only the `client_id`
+    /// parameter, or using `Basic` authentication with an empty password.
+    pub fn register_client(&self, client_id: &str, client_secret: Option<&str>) {
+        self.lock()
+            .clients
+            .insert(client_id.to_owned(), client_secret.map(ToOwned::to_owned));
+    }
+
+    /// Queue a response to be served by the given endpoint.
+    ///
+    /// Queued responses are served in order, each one for a single request,
+    /// and take precedence over the built-in behavior. Requests served this
+    /// way have no other effect, such as consuming an authorization code.
+    pub fn enqueue(&self, endpoint: Endpoint, response: MockResponse) {
+        self.lock().queued.push((endpoint, response));
+    }
+
+    /// The number of queued responses which have not yet been served.
+    pub fn queued(&self) -> usize {
+        self.lock().queued.len()
+    }
+
+    /// All requests received so far, in order.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.lock().requests.clone()
+    }
+
+    /// All requests received so far by the given endpoint, in order.
+    pub fn requests_to(&self, endpoint: Endpoint) -> Vec<RecordedRequest> {
+        self.lock()
+            .requests
+            .iter()
+            .filter(|request| request.endpoint == Some(endpoint))
+            .cloned()
+            .collect()
+    }
+
+    /// Simulate a user-agent visiting the given authorization URL and the
+    /// user approving the request, returning the URL the user-agent is
+    /// redirected to.
+    ///
+    /// This goes through the same logic as a request sent to the
+    /// authorization endpoint, and is recorded in the same way. It returns
+    /// `None` if the server doesn't redirect, such as if the request has no
+    /// `redirect_uri` parameter.
+    pub fn authorize(&self, url: &Url) -> Option<Url> {
+        let request = RecordedRequest {
+            endpoint: Some(Endpoint::Authorize),
+            method: Method::GET,
+            path: url.path().to_owned(),
+            headers: HeaderMap::new(),
+            body: Bytes::new(),
+            query: pairs(url.query().unwrap_or_default().as_bytes()),
+            form: Vec::new(),
+        };
+
+        let response = handle(&self.shared, request);
+        let location = response.headers.get(LOCATION)?.to_str().ok()?;
+        Url::parse(location).ok()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Shared> {
+        lock(&self.shared)
+    }
+}
+
+impl fmt::Debug for MockServer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MockServer")
+            .field("addr", &self.addr)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// State shared between the server and its handle.
+#[derive(Default)]
+struct Shared {
+    clients: HashMap<String, Option<String>>,
+    queued: Vec<(Endpoint, MockResponse)>,
+    requests: Vec<RecordedRequest>,
+    /// Authorization codes which have been issued but not yet exchanged.
+    codes: HashMap<String, IssuedCode>,
+    /// Backchannel authentication requests which have not yet been
+    /// exchanged, by `auth_req_id`.
+    backchannel: HashMap<String, Grant>,
+    /// Issued access and refresh tokens.
+    tokens: HashMap<String, IssuedToken>,
+    counter: u64,
+}
+
+impl Shared {
+    fn next(&mut self) -> u64 {
+        self.counter += 1;
+        self.counter
+    }
+
+    /// Issue a token for the given grant, returning the token response.
+    fn issue(&mut self, grant: Grant, refresh: bool) -> serde_json::Value {
+        let n = self.next();
+        let access_token = format!("mock-access-token-{n}");
+
+        let mut body = json!({
+            "access_token": access_token,
+            "token_type": "bearer",
+            "expires_in": EXPIRES_IN,
+        });
+
+        if let Some(scope) = &grant.scope {
+            body["scope"] = json!(scope);
+        }
+
+        if refresh {
+            let refresh_token = format!("mock-refresh-token-{n}");
+            body["refresh_token"] = json!(refresh_token);
+            self.tokens.insert(
+                refresh_token,
+                IssuedToken::new(grant.clone(), "refresh_token"),
+            );
+        }
+
+        self.tokens
+            .insert(access_token, IssuedToken::new(grant, "access_token"));
+        body
+    }
+}
+
+/// The client and scope a token was issued to.
+#[derive(Clone)]
+struct Grant {
+    client_id: String,
+    scope: Option<String>,
+}
+
+struct IssuedCode {
+    grant: Grant,
+    redirect_uri: String,
+    code_challenge: Option<(String, String)>,
+}
+
+struct IssuedToken {
+    grant: Grant,
+    kind: &'static str,
+    revoked: bool,
+}
+
+impl IssuedToken {
+    fn new(grant: Grant, kind: &'static str) -> Self {
+        Self {
+            grant,
+            kind,
+            revoked: false,
+        }
+    }
+}
+
+fn lock(shared: &Mutex<Shared>) -> MutexGuard<'_, Shared> {
+    // A panicking test shouldn't poison the server for other tests.
+    shared.lock().unwrap_or_else(|error| error.into_inner())
+}
+
+async fn accept(listener: TcpListener, shared: Arc<Mutex<Shared>>) {
+    loop {
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(..) => {
+                // Errors such as running out of file descriptors are
+                // usually transient, so back off instead of spinning.
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                continue;
+            }
+        };
+
+        let shared = shared.clone();
+
+        let service = service_fn(move |request: http::Request<Incoming>| {
+            let shared = shared.clone();
+
+            async move {
+                let (parts, body) = request.into_parts();
+                let body = body.collect().await?.to_bytes();
+
+                let request = RecordedRequest {
+                    endpoint: Endpoint::from_path(parts.uri.path()),
+                    method: parts.method,
+                    path: parts.uri.path().to_owned(),
+                    query: pairs(parts.uri.query().unwrap_or_default().as_bytes()),
+                    form: pairs(&body),
+                    headers: parts.headers,
+                    body,
+                };
+
+                let response = handle(&shared, request);
+
+                if let Some(delay) = response.delay {
+                    tokio::time::sleep(delay).await;
+                }
+
+                Ok::<_, hyper::Error>(response.into_response())
+            }
+        });
+
+        tokio::spawn(async move {
+            let _ = http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .await;
+        });
+    }
+}
+
+/// Record and respond to a request.
+fn handle(shared: &Mutex<Shared>, request: RecordedRequest) -> MockResponse {
+    let mut shared = lock(shared);
+    shared.requests.push(request.clone());
+
+    let endpoint = request.endpoint;
+
+    if let Some(index) = shared
+        .queued
+        .iter()
+        .position(|(queued, _)| Some(*queued) == endpoint)
+    {
+        let (_, response) = shared.queued.remove(index);
+        return response;
+    }
+
+    match endpoint {
+        Some(Endpoint::Authorize) => {
+            if request.method != Method::GET && request.method != Method::POST {
+                return MockResponse::new(StatusCode::METHOD_NOT_ALLOWED);
+            }
+
+            authorize(&mut shared, &request)
+        }
+        Some(endpoint) => {
+            if request.method != Method::POST {
+                return MockResponse::new(StatusCode::METHOD_NOT_ALLOWED);
+            }
+
+            match authenticate(&shared, &request) {
+                Some(client_id) => match endpoint {
+                    Endpoint::Token => token(&mut shared, &request, client_id),
+                    Endpoint::Revocation => revoke(&mut shared, &request),
+                    Endpoint::Introspection => introspect(&shared, &request),
+                    Endpoint::BackchannelAuthentication => {
+                        backchannel(&mut shared, &request, client_id)
+                    }
+                    Endpoint::Authorize => unreachable!(),
+                },
+                None => MockResponse::error(StatusCode::UNAUTHORIZED, ErrorField::InvalidClient)
+                    .header(WWW_AUTHENTICATE, HeaderValue::from_static("Basic")),
+            }
+        }
+        None => MockResponse::new(StatusCode::NOT_FOUND),
+    }
+}
+
+/// Authenticate the client of a request, returning its identifier.
+fn authenticate(shared: &Shared, request: &RecordedRequest) -> Option<String> {
+    let (client_id, client_secret) = request.client_credentials()?;
+
+    if !shared.clients.is_empty() && shared.clients.get(&client_id) != Some(&client_secret) {
+        return None;
+    }
+
+    Some(client_id)
+}
+
+fn authorize(shared: &mut Shared, request: &RecordedRequest) -> MockResponse {
+    let (Some(client_id), Some(redirect_uri)) =
+        (request.param("client_id"), request.param("redirect_uri"))
+    else {
+        return MockResponse::error(StatusCode::BAD_REQUEST, ErrorField::InvalidRequest);
+    };
+
+    let Ok(mut redirect) = Url::parse(redirect_uri) else {
+        return MockResponse::error(StatusCode::BAD_REQUEST, ErrorField::InvalidRequest);
+    };
+
+    if !shared.clients.is_empty() && !shared.clients.contains_key(client_id) {
+        return MockResponse::error(StatusCode::BAD_REQUEST, ErrorField::InvalidClient);
+    }
+
+    let grant = Grant {
+        client_id: client_id.to_owned(),
+        scope: request.param("scope").map(ToOwned::to_owned),
+    };
+
+    let mut params = Vec::new();
+
+    match request.param("response_type") {
+        Some("code") => {
+            let code_challenge = match (
+                request.param("code_challenge"),
+                request.param("code_challenge_method").unwrap_or("plain"),
+            ) {
+                (Some(challenge), method @ ("plain" | "S256")) => {
+                    Some((challenge.to_owned(), method.to_owned()))
+                }
+                (Some(..), _) => {
+                    params.push(("error", ErrorField::InvalidRequest.as_str().to_owned()));
+                    None
+                }
+                (None, _) => None,
+            };
+
+            if params.is_empty() {
+                let code = format!("mock-code-{}", shared.next());
+                params.push(("code", code.clone()));
+
+                shared.codes.insert(
+                    code,
+                    IssuedCode {
+                        grant,
+                        redirect_uri: redirect_uri.to_owned(),
+                        code_challenge,
+                    },
+                );
+            }
+        }
+        Some("token") => {
+            let token = shared.issue(grant, false);
+
+            for key in ["access_token", "token_type", "expires_in", "scope"] {
+                match &token[key] {
+                    serde_json::Value::String(value) => params.push((key, value.clone())),
+                    serde_json::Value::Number(value) => params.push((key, value.to_string())),
+                    _ => {}
+                }
+            }
+        }
+        _ => {
+            params.push(("error", "unsupported_response_type".to_owned()));
+        }
+    }
+
+    if let Some(state) = request.param("state") {
+        params.push(("state", state.to_owned()));
+    }
+
+    // The implicit grant returns its parameters in the fragment, see
+    // https://tools.ietf.org/html/rfc6749#section-4.2.2
+    if request.param("response_type") == Some("token") {
+        let fragment = url::form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(params)
+            .finish();
+        redirect.set_fragment(Some(&fragment));
+    } else {
+        redirect.query_pairs_mut().extend_pairs(params);
+    }
+
+    let location = HeaderValue::try_from(redirect.as_str()).expect("url should be a valid header");
+    MockResponse::new(StatusCode::FOUND).header(LOCATION, location)
+}
+
+fn token(shared: &mut Shared, request: &RecordedRequest, client_id: String) -> MockResponse {
+    let invalid_request =
+        || MockResponse::error(StatusCode::BAD_REQUEST, ErrorField::InvalidRequest);
+    let invalid_grant = || MockResponse::error(StatusCode::BAD_REQUEST, ErrorField::InvalidGrant);
+
+    let scope = request.form("scope").map(ToOwned::to_owned);
+
+    let body = match request.form("grant_type") {
+        Some("authorization_code") => {
+            let Some(code) = request.form("code") else {
+                return invalid_request();
+            };
+
+            // Codes can only be used once, even if the exchange fails.
+            let Some(issued) = shared.codes.remove(code) else {
+                return invalid_grant();
+            };
+
+            if issued.grant.client_id != client_id
+                || request.form("redirect_uri") != Some(issued.redirect_uri.as_str())
+            {
+                return invalid_grant();
+            }
+
+            if let Some((challenge, method)) = &issued.code_challenge {
+                let Some(verifier) = request.form("code_verifier") else {
+                    return invalid_grant();
+                };
+
+                let computed = if method == "S256" {
+                    BASE64_URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+                } else {
+                    verifier.to_owned()
+                };
+
+                if computed != *challenge {
+                    return invalid_grant();
+                }
+            }
+
+            shared.issue(issued.grant, true)
+        }
+        Some("refresh_token") => {
+            let Some(refresh_token) = request.form("refresh_token") else {
+                return invalid_request();
+            };
+
+            let Some(issued) = shared.tokens.get(refresh_token) else {
+                return invalid_grant();
+            };
+
+            if issued.kind != "refresh_token"
+                || issued.revoked
+                || issued.grant.client_id != client_id
+            {
+                return invalid_grant();
+            }
+
+            let grant = Grant {
+                client_id,
+                scope: scope.or_else(|| issued.grant.scope.clone()),
+            };
+
+            shared.issue(grant, false)
+        }
+        Some("password") => {
+            if request.form("username").is_none() || request.form("password").is_none() {
+                return invalid_request();
+            }
+
+            shared.issue(Grant { client_id, scope }, true)
+        }
+        Some("client_credentials") => shared.issue(Grant { client_id, scope }, false),
+        Some("urn:ietf:params:oauth:grant-type:saml2-bearer") => {
+            if request.form("assertion").is_none() {
+                return invalid_request();
+            }
+
+            shared.issue(Grant { client_id, scope }, false)
+        }
+        Some("urn:openid:params:grant-type:ciba") => {
+            let Some(auth_req_id) = request.form("auth_req_id") else {
+                return invalid_request();
+            };
+
+            let Some(grant) = shared.backchannel.remove(auth_req_id) else {
+                return invalid_grant();
+            };
+
+            if grant.client_id != client_id {
+                return invalid_grant();
+            }
+
+            shared.issue(grant, true)
+        }
+        Some(..) => {
+            return MockResponse::error(StatusCode::BAD_REQUEST, ErrorField::UnsupportedGrantType)
+        }
+        None => return invalid_request(),
+    };
+
+    MockResponse::json(StatusCode::OK, &body)
+}
+
+fn revoke(shared: &mut Shared, request: &RecordedRequest) -> MockResponse {
+    let Some(token) = request.form("token") else {
+        return MockResponse::error(StatusCode::BAD_REQUEST, ErrorField::InvalidRequest);
+    };
+
+    // Invalid tokens don't cause an error response, see
+    // https://tools.ietf.org/html/rfc7009#section-2.2
+    if let Some(issued) = shared.tokens.get_mut(token) {
+        issued.revoked = true;
+    }
+
+    MockResponse::new(StatusCode::OK)
+}
+
+fn introspect(shared: &Shared, request: &RecordedRequest) -> MockResponse {
+    let Some(token) = request.form("token") else {
+        return MockResponse::error(StatusCode::BAD_REQUEST, ErrorField::InvalidRequest);
+    };
+
+    let body = match shared.tokens.get(token) {
+        Some(issued) if !issued.revoked => {
+            let mut body = json!({
+                "active": true,
+                "client_id": issued.grant.client_id,
+                "token_type": if issued.kind == "access_token" { "bearer" } else { "refresh_token" },
+            });
+
+            if let Some(scope) = &issued.grant.scope {
+                body["scope"] = json!(scope);
+            }
+
+            body
+        }
+        _ => json!({ "active": false }),
+    };
+
+    MockResponse::json(StatusCode::OK, &body)
+}
+
+fn backchannel(shared: &mut Shared, request: &RecordedRequest, client_id: String) -> MockResponse {
+    let hints = ["login_hint", "id_token_hint", "login_hint_token"];
+
+    if hints
+        .iter()
+        .filter(|hint| request.form(hint).is_some())
+        .count()
+        != 1
+    {
+        return MockResponse::error(StatusCode::BAD_REQUEST, ErrorField::InvalidRequest);
+    }
+
+    let auth_req_id = format!("mock-auth-req-id-{}", shared.next());
+
+    shared.backchannel.insert(
+        auth_req_id.clone(),
+        Grant {
+            client_id,
+            scope: request.form("scope").map(ToOwned::to_owned),
+        },
+    );
+
+    MockResponse::json(
+        StatusCode::OK,
+        &json!({
+            "auth_req_id": auth_req_id,
+            "expires_in": 120,
+        }),
+    )
+}
+
+fn find<'a>(pairs: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    pairs
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.as_str())
+}
+
+fn pairs(input: &[u8]) -> Vec<(String, String)> {
+    url::form_urlencoded::parse(input).into_owned().collect()
+}
+
+fn form_decode(input: &str) -> String {
+    url::form_urlencoded::parse(input.as_bytes())
+        .next()
+        .map(|(key, _)| key.into_owned())
+        .unwrap_or_default()
+}
@@ -0,0 +1,457 @@
+//! A transport which records token requests and replays canned responses,
+//! for golden-file testing of the exact wire format.
+//!
+//! Requests are executed through [`Request::with_recorder`], which encodes
+//! the request exactly like the network transports do, records it as a
+//! [`Recording`] and responds with the next response queued on the
+//! [`Recorder`]. No network access is performed.
+//!
+//! Secrets are redacted from recordings, so that they can be checked in:
+//! * Form parameters have their values replaced by `[redacted]`, except for
+//!   the `grant_type`, `scope`, `redirect_uri` and `client_id` parameters
+//!   which are known not to be secret and are kept byte for byte. The
+//!   `username` of password grants is redacted as well, since it identifies
+//!   a resource owner.
+//!   This includes parameters added through [`Request::param`], such as
+//!   extension grant parameters.
+//! * A `Basic` `Authorization` header is decoded and rendered as
+//!   `Basic <client_id>:[redacted]`, where `<client_id>` is the url-encoded
+//!   client identifier as it was sent.
+//!
+//! A recording is rendered as follows, and can be compared against a file
+//! using [`Recording::assert_golden`]:
+//!
+//! ```text
+//! POST http://token/
+//! accept: application/json
+//! authorization: Basic client%2Bid:[redacted]
+//! content-type: application/x-www-form-urlencoded
+//!
+//! grant_type=client_credentials&scope=read
+//! ```
+//!
+//! Responses are queued through [`Recorder::replay`] or loaded from files
+//! through [`Recorder::replay_file`], which use the following format:
+//!
+//! ```text
+//! HTTP/1.1 200 OK
+//! content-type: application/json
+//!
+//! {"access_token":"access_token","token_type":"bearer"}
+//! ```
+//!
+//! # Examples
+//!
+//! ```
+//! use http::StatusCode;
+//! use oauth2::recording::{Recorder, Replay};
+//! use oauth2::{Client, StandardToken};
+//! use url::Url;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut client = Client::new(
+//!     "client+id",
+//!     Url::parse("http://authorize")?,
+//!     Url::parse("http://token")?
+//! );
+//!
+//! client.set_client_secret("client_secret");
+//! client.add_scope("read");
+//!
+//! let recorder = Recorder::new();
+//!
+//! recorder.replay(Replay::json(
+//!     StatusCode::OK,
+//!     r#"{"access_token":"access_token","token_type":"bearer"}"#,
+//! ));
+//!
+//! let token = client
+//!     .exchange_client_credentials()
+//!     .with_recorder(&recorder)
+//!     .execute::<StandardToken>()?;
+//!
+//! let recordings = recorder.recordings();
+//!
+//! assert_eq!(
+//!     recordings[0].to_string(),
+//!     "POST http://token/\n\
+//!      accept: application/json\n\
+//!      authorization: Basic client%2Bid:[redacted]\n\
+//!      content-type: application/x-www-form-urlencoded\n\
+//!      \n\
+//!      grant_type=client_credentials&scope=read\n"
+//! );
+//! # Ok(()) }
+//! ```
+//!
+//! [`Request::with_recorder`]: crate::Request::with_recorder
+
+use core::fmt;
+
+use alloc::borrow::ToOwned;
+use alloc::collections::VecDeque;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+use base64::prelude::{Engine as _, BASE64_STANDARD};
+use bytes::Bytes;
+use http::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
+use http::{Method, StatusCode};
+use serde::Deserialize;
+use url::Url;
+
+use crate::transport::parse_response;
+use crate::{ExecuteError, ExecuteErrorKind, Request};
+
+/// The environment variable which causes [`Recording::assert_golden`] to
+/// update golden files instead of comparing against them.
+pub const UPDATE_GOLDEN: &str = "OAUTH2_UPDATE_GOLDEN";
+
+/// Form parameters whose values are kept in recordings. All other values are
+/// redacted, since parameters added by extensions can't be known in advance.
+const SAFE_PARAMS: &[&str] = &["grant_type", "scope", "redirect_uri", "client_id"];
+
+const REDACTED: &str = "[redacted]";
+
+/// Records token requests and replays canned responses.
+///
+/// See the [module level documentation](self).
+#[derive(Default)]
+pub struct Recorder {
+    inner: Mutex<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    replays: VecDeque<Replay>,
+    recordings: Vec<Recording>,
+}
+
+impl Recorder {
+    /// Construct a new recorder with no queued responses.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a response to be replayed.
+    ///
+    /// Responses are replayed in the order they were queued, each one for a
+    /// single request.
+    pub fn replay(&self, replay: Replay) {
+        self.lock().replays.push_back(replay);
+    }
+
+    /// Queue a response loaded from the given file.
+    ///
+    /// See [`Replay::parse`] for the format of the file.
+    pub fn replay_file(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let contents = fs::read_to_string(path)?;
+        self.replay(Replay::parse(&contents)?);
+        Ok(())
+    }
+
+    /// All requests recorded so far, in order.
+    pub fn recordings(&self) -> Vec<Recording> {
+        self.lock().recordings.clone()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        // A panicking test shouldn't poison the recorder for other tests.
+        self.inner.lock().unwrap_or_else(|error| error.into_inner())
+    }
+}
+
+impl fmt::Debug for Recorder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let inner = self.lock();
+
+        f.debug_struct("Recorder")
+            .field("replays", &inner.replays)
+            .field("recordings", &inner.recordings)
+            .finish()
+    }
+}
+
+/// A request wrapped in a [`Recorder`], ready to be executed.
+#[derive(Clone)]
+pub struct RecorderRequest<'a> {
+    request: Request<'a>,
+    recorder: &'a Recorder,
+}
+
+impl<'a> RecorderRequest<'a> {
+    pub(crate) fn new(request: Request<'a>, recorder: &'a Recorder) -> Self {
+        Self { request, recorder }
+    }
+
+    /// Execute the token request, recording it and responding with the next
+    /// queued response.
+    ///
+    /// The response is handled exactly like one received over the network.
+    /// If no response is queued, an error classified as
+    /// [`ErrorKind::Transport`] is returned. Requests which are refused due
+    /// to strict mode are not recorded.
+    ///
+    /// [`ErrorKind::Transport`]: crate::ErrorKind::Transport
+    pub fn execute<T>(self) -> Result<T, ExecuteError>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let (headers, body) = self.request.encode()?;
+
+        let replay = {
+            let mut inner = self.recorder.lock();
+
+            inner.recordings.push(Recording::new(
+                self.request.token_url.clone(),
+                &headers,
+                &body,
+            ));
+
+            inner.replays.pop_front()
+        };
+
+        let Some(replay) = replay else {
+            return Err(ExecuteError::from(ExecuteErrorKind::NoReplay));
+        };
+
+        parse_response(
+            replay.status,
+            replay.headers,
+            replay.body,
+            self.request.detect_success_errors,
+        )
+    }
+}
+
+/// A canned response replayed by a [`Recorder`].
+#[derive(Debug, Clone)]
+pub struct Replay {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+impl Replay {
+    /// Construct an empty response with the given status code.
+    pub fn new(status: StatusCode) -> Self {
+        Self {
+            status,
+            headers: HeaderMap::new(),
+            body: Bytes::new(),
+        }
+    }
+
+    /// Construct a response with a JSON body.
+    pub fn json(status: StatusCode, body: impl Into<Bytes>) -> Self {
+        Self::new(status)
+            .header(CONTENT_TYPE, HeaderValue::from_static("application/json"))
+            .body(body)
+    }
+
+    /// Add a header to the response.
+    pub fn header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.append(name, value);
+        self
+    }
+
+    /// Set the body of the response.
+    pub fn body(mut self, body: impl Into<Bytes>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /// Parse a response from a status line such as `HTTP/1.1 200 OK`,
+    /// followed by headers, an empty line and the body.
+    ///
+    /// Line endings may be either `\n` or `\r\n`. The body is used as is,
+    /// except for a single trailing line ending which is removed.
+    pub fn parse(input: &str) -> io::Result<Self> {
+        fn invalid(message: &str) -> io::Error {
+            io::Error::new(io::ErrorKind::InvalidData, message)
+        }
+
+        let (head, body) = match input.split_once("\r\n\r\n") {
+            Some(split) => split,
+            None => input.split_once("\n\n").unwrap_or((input, "")),
+        };
+
+        let mut lines = head.lines();
+
+        let status = lines
+            .next()
+            .and_then(|line| line.split_whitespace().nth(1))
+            .ok_or_else(|| invalid("missing status line"))?;
+
+        let status =
+            StatusCode::from_bytes(status.as_bytes()).map_err(|_| invalid("invalid status"))?;
+
+        let mut replay = Self::new(status);
+
+        for line in lines {
+            let (name, value) = line
+                .split_once(':')
+                .ok_or_else(|| invalid("invalid header line"))?;
+
+            let name = HeaderName::try_from(name.trim()).map_err(|_| invalid("invalid header"))?;
+            let value =
+                HeaderValue::try_from(value.trim()).map_err(|_| invalid("invalid header"))?;
+            replay = replay.header(name, value);
+        }
+
+        let body = body
+            .strip_suffix("\r\n")
+            .or_else(|| body.strip_suffix('\n'))
+            .unwrap_or(body);
+
+        Ok(replay.body(body.to_owned()))
+    }
+}
+
+/// A token request recorded by a [`Recorder`], with secrets redacted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recording {
+    method: Method,
+    url: Url,
+    headers: Vec<(HeaderName, String)>,
+    body: String,
+}
+
+impl Recording {
+    fn new(url: Url, headers: &HeaderMap, body: &[u8]) -> Self {
+        let mut headers = headers
+            .iter()
+            .map(|(name, value)| (name.clone(), redact_header(name, value)))
+            .collect::<Vec<_>>();
+
+        headers.sort_by(|a, b| a.0.as_str().cmp(b.0.as_str()));
+
+        Self {
+            method: Method::POST,
+            url,
+            headers,
+            body: redact_form(&String::from_utf8_lossy(body)),
+        }
+    }
+
+    /// The method of the request.
+    pub fn method(&self) -> &Method {
+        &self.method
+    }
+
+    /// The URL the request was sent to.
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// Get the redacted value of a header.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The redacted form encoded body of the request.
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+
+    /// Assert that the rendered recording matches the contents of the
+    /// given file.
+    ///
+    /// If the [`UPDATE_GOLDEN`] environment variable is set, the file is
+    /// written instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the file doesn't exist or its contents differ.
+    #[track_caller]
+    pub fn assert_golden(&self, path: impl AsRef<Path>) {
+        let path = path.as_ref();
+        let actual = self.to_string();
+
+        if env::var_os(UPDATE_GOLDEN).is_some() {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).expect("creating golden file directory");
+            }
+
+            fs::write(path, &actual).expect("writing golden file");
+            return;
+        }
+
+        let expected = match fs::read_to_string(path) {
+            Ok(expected) => expected,
+            Err(error) => panic!(
+                "{}: {error}, set {UPDATE_GOLDEN}=1 to create it",
+                path.display()
+            ),
+        };
+
+        // Golden files may have been checked out with CRLF line endings.
+        let expected = expected.replace("\r\n", "\n");
+
+        if expected != actual {
+            panic!(
+                "{} doesn't match, set {UPDATE_GOLDEN}=1 to update it\n\
+                 --- expected\n{expected}\n--- actual\n{actual}",
+                path.display()
+            );
+        }
+    }
+}
+
+impl fmt::Display for Recording {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} {}", self.method, self.url)?;
+
+        for (name, value) in &self.headers {
+            writeln!(f, "{name}: {value}")?;
+        }
+
+        writeln!(f)?;
+        writeln!(f, "{}", self.body)
+    }
+}
+
+/// Render a header value, redacting the secret from client credentials.
+fn redact_header(name: &HeaderName, value: &HeaderValue) -> String {
+    if *name != AUTHORIZATION {
+        return String::from_utf8_lossy(value.as_bytes()).into_owned();
+    }
+
+    let basic = value
+        .to_str()
+        .ok()
+        .and_then(|value| value.strip_prefix("Basic "))
+        .and_then(|credentials| BASE64_STANDARD.decode(credentials).ok())
+        .and_then(|credentials| String::from_utf8(credentials).ok());
+
+    match basic.as_deref().and_then(|basic| basic.split_once(':')) {
+        Some((client_id, _)) => format!("Basic {client_id}:{REDACTED}"),
+        None => REDACTED.to_string(),
+    }
+}
+
+/// Redact the values of all but the known safe parameters in a form encoded
+/// body.
+fn redact_form(body: &str) -> String {
+    let pairs = body.split('&').map(|pair| {
+        let (key, _) = pair.split_once('=').unwrap_or((pair, ""));
+
+        if SAFE_PARAMS.contains(&key) {
+            pair.to_owned()
+        } else {
+            format!("{key}={REDACTED}")
+        }
+    });
+
+    pairs.collect::<Vec<_>>().join("&")
+}
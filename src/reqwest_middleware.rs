@@ -0,0 +1,169 @@
+//! Integration with [reqwest-middleware].
+//!
+//! This provides [`BearerAuthMiddleware`], which authenticates every request
+//! sent through a [`ClientWithMiddleware`] using the token provided by a
+//! [`TokenManager`].
+//!
+//! ```no_run
+//! use oauth2::reqwest_middleware::BearerAuthMiddleware;
+//! use oauth2::{Client, TokenManager};
+//! use url::Url;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut client = Client::new(
+//!     "client_id",
+//!     Url::parse("http://authorize")?,
+//!     Url::parse("http://token")?
+//! );
+//!
+//! client.set_client_secret("client_secret");
+//!
+//! let manager = TokenManager::new(client, reqwest::Client::new());
+//!
+//! let api = reqwest_middleware::ClientBuilder::new(reqwest::Client::new())
+//!     .with(BearerAuthMiddleware::new(manager))
+//!     .build();
+//!
+//! let response = api.get("https://api.example.com/me").send().await?;
+//! # Ok(()) }
+//! ```
+//!
+//! [reqwest-middleware]: https://docs.rs/reqwest-middleware
+//! [`ClientWithMiddleware`]: ::reqwest_middleware::ClientWithMiddleware
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use http::header::{AUTHORIZATION, WWW_AUTHENTICATE};
+use http::{Extensions, StatusCode};
+use reqwest::{Request, Response};
+
+use ::reqwest_middleware::{Error, Middleware, Next, Result};
+
+use crate::{Bearer, ExecuteError, ExecuteErrorKind, StandardToken, Token, TokenManager};
+
+/// A [`Middleware`] which authenticates requests with an `Authorization`
+/// header using the token provided by a [`TokenManager`].
+///
+/// The header is constructed through [`Bearer::header_value`], so only
+/// bearer tokens can be used.
+///
+/// If the server responds with `401 Unauthorized` and an `invalid_token`
+/// error in its `WWW-Authenticate` header, as described in
+/// [Section 3.1 of RFC 6750](https://tools.ietf.org/html/rfc6750#section-3.1),
+/// a new token is requested and the request is retried once. Requests with
+/// streaming bodies can't be retried, in which case the response is returned
+/// as is.
+#[derive(Debug, Clone)]
+pub struct BearerAuthMiddleware {
+    manager: TokenManager,
+}
+
+impl BearerAuthMiddleware {
+    /// Construct a new middleware using tokens from the given manager.
+    pub fn new(manager: TokenManager) -> Self {
+        Self { manager }
+    }
+}
+
+#[async_trait::async_trait]
+impl Middleware for BearerAuthMiddleware {
+    async fn handle(
+        &self,
+        request: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> Result<Response> {
+        let token = self
+            .manager
+            .token_replacing(None)
+            .await
+            .map_err(Error::middleware)?;
+
+        let retry = request.try_clone();
+
+        let response = next
+            .clone()
+            .run(authorize(request, &token)?, extensions)
+            .await?;
+
+        if response.status() != StatusCode::UNAUTHORIZED || !is_invalid_token(&response) {
+            return Ok(response);
+        }
+
+        let Some(retry) = retry else {
+            return Ok(response);
+        };
+
+        let token = self
+            .manager
+            .token_replacing(Some(token.access_token()))
+            .await
+            .map_err(Error::middleware)?;
+
+        next.run(authorize(retry, &token)?, extensions).await
+    }
+}
+
+/// Add the `Authorization` header for the given token to a request.
+fn authorize(mut request: Request, token: &StandardToken) -> Result<Request> {
+    let value = Bearer::new(token).header_value().map_err(|error| {
+        Error::middleware(ExecuteError::from(ExecuteErrorKind::InvalidAccessToken {
+            error,
+        }))
+    })?;
+
+    request.headers_mut().insert(AUTHORIZATION, value);
+    Ok(request)
+}
+
+/// Test if the response indicates that the access token was rejected through
+/// an `error="invalid_token"` parameter in a `WWW-Authenticate` header.
+fn is_invalid_token(response: &Response) -> bool {
+    let values = response.headers().get_all(WWW_AUTHENTICATE);
+
+    values
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(split_params)
+        .filter_map(|param| {
+            let (name, value) = param.split_once('=')?;
+            // The first parameter is preceded by the authentication scheme.
+            let name = name.split_whitespace().next_back()?;
+            Some((name, value.trim()))
+        })
+        .any(|(name, value)| {
+            let value = value
+                .strip_prefix('"')
+                .and_then(|value| value.strip_suffix('"'))
+                .unwrap_or(value);
+
+            name.eq_ignore_ascii_case("error") && value == "invalid_token"
+        })
+}
+
+/// Split the value of a `WWW-Authenticate` header at each comma which is not
+/// part of a quoted string.
+fn split_params(value: &str) -> Vec<&str> {
+    let mut params = Vec::new();
+    let mut start = 0;
+    let mut quoted = false;
+    let mut escaped = false;
+
+    for (index, c) in value.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            ',' if !quoted => {
+                params.push(&value[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+
+    params.push(&value[start..]);
+    params
+}
@@ -0,0 +1,255 @@
+//! Integration with [tower].
+//!
+//! This provides:
+//! * [`TokenService`], which executes token requests as a
+//!   [`Service<Request>`][Service].
+//! * [`BearerAuthLayer`], which wraps an HTTP service such that every request
+//!   passing through it is authenticated with an `Authorization` header using
+//!   the token provided by a [`TokenManager`].
+//!
+//! [`TokenManager`]: crate::TokenManager
+//!
+//! ```no_run
+//! use oauth2::tower::BearerAuthLayer;
+//! use oauth2::{Client, TokenManager};
+//! use tower::{Layer, Service, ServiceExt};
+//! use url::Url;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+//! let mut client = Client::new(
+//!     "client_id",
+//!     Url::parse("http://authorize")?,
+//!     Url::parse("http://token")?
+//! );
+//!
+//! client.set_client_secret("client_secret");
+//!
+//! let manager = TokenManager::new(client, reqwest::Client::new());
+//!
+//! let service = tower::service_fn(|request: http::Request<String>| async move {
+//!     assert!(request.headers().contains_key(http::header::AUTHORIZATION));
+//!     Ok::<_, Box<dyn std::error::Error + Send + Sync>>(http::Response::new(String::new()))
+//! });
+//!
+//! let mut service = BearerAuthLayer::new(manager).layer(service);
+//!
+//! let response = service
+//!     .ready()
+//!     .await?
+//!     .call(http::Request::new(String::new()))
+//!     .await?;
+//! # Ok(()) }
+//! ```
+//!
+//! [tower]: https://docs.rs/tower
+
+use core::fmt;
+use core::future::{poll_fn, Future};
+use core::marker::PhantomData;
+use core::mem;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use alloc::boxed::Box;
+
+use http::header::AUTHORIZATION;
+use http::StatusCode;
+use serde::Deserialize;
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::{Bearer, ExecuteError, ExecuteErrorKind, Request, StandardToken, Token, TokenManager};
+
+/// A boxed future, as returned by the services in this module.
+type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// A [`Service`] which executes token requests using [`reqwest`], resolving
+/// into a deserialized token of type `T`.
+///
+/// This allows token requests to be composed with other tower middleware,
+/// such as rate or concurrency limits.
+///
+/// [`reqwest`]: https://docs.rs/reqwest
+pub struct TokenService<T = StandardToken> {
+    client: reqwest::Client,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> TokenService<T> {
+    /// Construct a new token service using the given reqwest client.
+    pub fn new(client: reqwest::Client) -> Self {
+        Self {
+            client,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Clone for TokenService<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self::new(self.client.clone())
+    }
+}
+
+impl<T> fmt::Debug for TokenService<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TokenService")
+            .field("client", &self.client)
+            .finish()
+    }
+}
+
+impl<'a, T> Service<Request<'a>> for TokenService<T>
+where
+    T: 'a + Send + for<'de> Deserialize<'de>,
+{
+    type Response = T;
+    type Error = ExecuteError;
+    type Future = BoxFuture<'a, Result<T, ExecuteError>>;
+
+    #[inline]
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: Request<'a>) -> Self::Future {
+        let client = self.client.clone();
+
+        Box::pin(async move { request.with_reqwest_client(&client).execute().await })
+    }
+}
+
+/// A [`Layer`] which authenticates requests using the access token provided
+/// by a [`TokenManager`].
+///
+/// See [`BearerAuth`].
+#[derive(Debug, Clone)]
+pub struct BearerAuthLayer {
+    manager: TokenManager,
+}
+
+impl BearerAuthLayer {
+    /// Construct a new layer using tokens from the given manager.
+    pub fn new(manager: TokenManager) -> Self {
+        Self { manager }
+    }
+}
+
+impl<S> Layer<S> for BearerAuthLayer {
+    type Service = BearerAuth<S>;
+
+    #[inline]
+    fn layer(&self, inner: S) -> Self::Service {
+        BearerAuth {
+            inner,
+            manager: self.manager.clone(),
+        }
+    }
+}
+
+/// A [`Service`] which authenticates requests with an `Authorization` header
+/// using the token provided by a [`TokenManager`].
+///
+/// The header is constructed through [`Bearer::header_value`], so only
+/// bearer tokens can be used.
+///
+/// If the inner service responds with `401 Unauthorized`, a new token is
+/// requested and the request is retried once. Since the request has to be
+/// kept around for this, its body must implement [`Clone`].
+///
+/// Errors encountered while requesting tokens are converted into the error
+/// type of the inner service, which therefore has to implement
+/// `From<ExecuteError>`. This is the case for boxed errors such as
+/// `Box<dyn Error + Send + Sync>`.
+#[derive(Debug, Clone)]
+pub struct BearerAuth<S> {
+    inner: S,
+    manager: TokenManager,
+}
+
+impl<S> BearerAuth<S> {
+    /// Wrap the given service.
+    pub fn new(inner: S, manager: TokenManager) -> Self {
+        Self { inner, manager }
+    }
+
+    /// Access the wrapped service.
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Convert into the wrapped service.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S, B, R> Service<http::Request<B>> for BearerAuth<S>
+where
+    S: Service<http::Request<B>, Response = http::Response<R>> + Clone + Send + 'static,
+    S::Future: Send,
+    S::Error: From<ExecuteError>,
+    B: Clone + Send + 'static,
+    R: Send,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<S::Response, S::Error>>;
+
+    #[inline]
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        // The service which was driven to readiness is the one which has to
+        // be called, so swap it out for a clone.
+        let clone = self.inner.clone();
+        let mut inner = mem::replace(&mut self.inner, clone);
+        let manager = self.manager.clone();
+
+        Box::pin(async move {
+            let token = manager.token_replacing(None).await?;
+            let retry = clone_request(&request);
+
+            let response = inner.call(authorize(request, &token)?).await?;
+
+            if response.status() != StatusCode::UNAUTHORIZED {
+                return Ok(response);
+            }
+
+            let token = manager.token_replacing(Some(token.access_token())).await?;
+            poll_fn(|cx| inner.poll_ready(cx)).await?;
+            inner.call(authorize(retry, &token)?).await
+        })
+    }
+}
+
+/// Clone a request so that it can be retried.
+fn clone_request<B>(request: &http::Request<B>) -> http::Request<B>
+where
+    B: Clone,
+{
+    let mut clone = http::Request::new(request.body().clone());
+    *clone.method_mut() = request.method().clone();
+    *clone.uri_mut() = request.uri().clone();
+    *clone.version_mut() = request.version();
+    *clone.headers_mut() = request.headers().clone();
+    *clone.extensions_mut() = request.extensions().clone();
+    clone
+}
+
+/// Add the `Authorization` header for the given token to a request.
+fn authorize<B>(
+    mut request: http::Request<B>,
+    token: &StandardToken,
+) -> Result<http::Request<B>, ExecuteError> {
+    let value = Bearer::new(token)
+        .header_value()
+        .map_err(|error| ExecuteErrorKind::InvalidAccessToken { error })?;
+
+    request.headers_mut().insert(AUTHORIZATION, value);
+    Ok(request)
+}
@@ -0,0 +1,301 @@
+//! Encoding of token requests and parsing of token responses, which is shared
+//! by all transports.
+
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use base64::prelude::{Engine as _, BASE64_STANDARD};
+use bytes::Bytes;
+use http::header::HeaderMap;
+use http::status::StatusCode;
+use serde::Deserialize;
+
+use crate::{AuthType, ErrorResponse, ExecuteError, ExecuteErrorKind, Request, StrictViolation};
+
+impl Request<'_> {
+    /// Encode the headers and body of the request, which are sent as a `POST`
+    /// request to the token URL by each transport.
+    pub(crate) fn encode(&self) -> Result<(HeaderMap, Vec<u8>), ExecuteError> {
+        use http::header::{self, HeaderValue};
+
+        const CONTENT_TYPE_JSON: &str = "application/json";
+
+        fn url_encode(s: &str) -> String {
+            url::form_urlencoded::byte_serialize(s.as_bytes()).collect::<String>()
+        }
+
+        self.check_strict()
+            .map_err(|violation| ExecuteErrorKind::Strict { violation })?;
+
+        let mut headers = HeaderMap::new();
+
+        // Section 5.1 of RFC 6749 (https://tools.ietf.org/html/rfc6749#section-5.1) only permits
+        // JSON responses for this request. Some providers such as GitHub have off-spec behavior
+        // and not only support different response formats, but have non-JSON defaults. Explicitly
+        // request JSON here, while still handling form-urlencoded responses in case the provider
+        // ignores it.
+        headers.insert(header::ACCEPT, HeaderValue::from_static(CONTENT_TYPE_JSON));
+
+        let mut form = url::form_urlencoded::Serializer::new(String::new());
+
+        // FIXME: add support for auth extensions? e.g., client_secret_jwt and private_key_jwt
+        match self.auth_type {
+            AuthType::RequestBody => {
+                form.append_pair("client_id", self.client_id);
+
+                if let Some(client_secret) = self.client_secret {
+                    form.append_pair("client_secret", client_secret);
+                }
+            }
+            AuthType::BasicAuth => {
+                // Section 2.3.1 of RFC 6749 requires separately url-encoding the id and secret
+                // before using them as HTTP Basic auth username and password. Note that this is
+                // not standard for ordinary Basic auth, so curl won't do it for us.
+                let mut credentials = url_encode(self.client_id);
+                credentials.push(':');
+
+                if let Some(client_secret) = self.client_secret {
+                    credentials.push_str(&url_encode(client_secret));
+                }
+
+                let mut value = String::from("Basic ");
+                BASE64_STANDARD.encode_string(credentials, &mut value);
+
+                let mut value =
+                    HeaderValue::try_from(value).expect("base64 is always a valid header value");
+                value.set_sensitive(true);
+                headers.insert(header::AUTHORIZATION, value);
+            }
+        }
+
+        for (key, value) in &self.params {
+            form.append_pair(key.as_ref(), value.as_ref());
+        }
+
+        if let Some(scopes) = &self.scopes {
+            if !scopes.is_empty() {
+                form.append_pair("scope", &scopes.to_string());
+            }
+        }
+
+        if let Some(redirect_url) = &self.redirect_url {
+            form.append_pair("redirect_uri", redirect_url.as_str());
+        }
+
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/x-www-form-urlencoded"),
+        );
+
+        Ok((headers, form.finish().into_bytes()))
+    }
+
+    /// Check that the request fulfills the requirements of strict mode, if
+    /// enabled.
+    fn check_strict(&self) -> Result<(), StrictViolation> {
+        if !self.strict {
+            return Ok(());
+        }
+
+        if self
+            .token_url
+            .query_pairs()
+            .any(|(key, _)| key == "access_token")
+        {
+            return Err(StrictViolation::BearerTokenInQuery);
+        }
+
+        let param = |name: &str| {
+            self.params
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.as_ref())
+        };
+
+        match param("grant_type") {
+            Some("password") => {
+                return Err(StrictViolation::PasswordGrant);
+            }
+            Some("authorization_code") => {
+                if param("code_verifier").is_none() {
+                    return Err(StrictViolation::MissingPkce);
+                }
+
+                let Some(redirect_url) = self.redirect_url else {
+                    return Err(StrictViolation::MissingRedirectUrl);
+                };
+
+                if redirect_url.fragment().is_some() {
+                    return Err(StrictViolation::RedirectUrlFragment);
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+}
+
+/// Parse the response to a token request.
+pub(crate) fn parse_response<T>(
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+    detect_success_errors: bool,
+) -> Result<T, ExecuteError>
+where
+    T: for<'de> Deserialize<'de>,
+{
+    let content_type = headers
+        .get(http::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok());
+
+    match parse_body(status, content_type, body, detect_success_errors) {
+        Ok(value) => Ok(value),
+        Err(kind) => Err(ExecuteError {
+            kind,
+            headers: Some(Box::new(headers)),
+        }),
+    }
+}
+
+/// Parse the body of the response to a token request.
+fn parse_body<T>(
+    status: StatusCode,
+    content_type: Option<&str>,
+    body: Bytes,
+    detect_success_errors: bool,
+) -> Result<T, ExecuteErrorKind>
+where
+    T: for<'de> Deserialize<'de>,
+{
+    /// Used to probe whether a successful response contains an error.
+    #[derive(Deserialize)]
+    struct Probe {
+        #[serde(default)]
+        error: Option<serde::de::IgnoredAny>,
+    }
+
+    if body.is_empty() {
+        return Err(ExecuteErrorKind::EmptyResponse { status });
+    }
+
+    let format = ResponseFormat::detect(content_type, &body);
+
+    let is_error = !status.is_success()
+        || (detect_success_errors
+            && format
+                .deserialize::<Probe>(&body)
+                .is_ok_and(|probe| probe.error.is_some()));
+
+    if is_error {
+        let error = match format.deserialize::<ErrorResponse>(&body) {
+            Ok(error) => error,
+            Err(error) => {
+                return Err(ExecuteErrorKind::BadResponse {
+                    status,
+                    error,
+                    body,
+                });
+            }
+        };
+
+        return Err(ExecuteErrorKind::ErrorResponse { status, error });
+    }
+
+    let value = format
+        .deserialize(&body)
+        .map_err(|error| ExecuteErrorKind::BadResponse {
+            status,
+            error,
+            body,
+        })?;
+
+    Ok(value)
+}
+
+/// The format of a token response body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ResponseFormat {
+    /// A JSON object, as required by
+    /// [Section 5.1 of RFC 6749](https://tools.ietf.org/html/rfc6749#section-5.1).
+    Json,
+    /// A form-urlencoded body, which some legacy providers respond with.
+    Form,
+}
+
+impl ResponseFormat {
+    /// Detect the format of a response based on its `Content-Type`.
+    ///
+    /// Providers which respond with `text/plain` have been seen to use both
+    /// JSON and form-urlencoded bodies, so in that case we look at the body
+    /// itself.
+    fn detect(content_type: Option<&str>, body: &[u8]) -> Self {
+        let Some(content_type) = content_type else {
+            return ResponseFormat::Json;
+        };
+
+        let essence = content_type.split(';').next().unwrap_or_default().trim();
+
+        if essence.eq_ignore_ascii_case("application/x-www-form-urlencoded") {
+            return ResponseFormat::Form;
+        }
+
+        if essence.eq_ignore_ascii_case("text/plain") {
+            let is_json = body
+                .iter()
+                .find(|b| !b.is_ascii_whitespace())
+                .is_some_and(|&b| b == b'{');
+
+            if !is_json {
+                return ResponseFormat::Form;
+            }
+        }
+
+        ResponseFormat::Json
+    }
+
+    /// Deserialize a body in the given format.
+    ///
+    /// Form-urlencoded bodies are converted into a JSON object of strings, so
+    /// that the same types can be used regardless of format. Since such
+    /// bodies carry no type information, deserialization is retried with
+    /// numeric values converted into numbers if that fails, which permits
+    /// numeric fields in token extensions.
+    fn deserialize<T>(self, body: &[u8]) -> Result<T, serde_json::Error>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        use serde_json::{Number, Value};
+
+        match self {
+            ResponseFormat::Json => serde_json::from_slice(body),
+            ResponseFormat::Form => {
+                let pairs = url::form_urlencoded::parse(body)
+                    .into_owned()
+                    .collect::<Vec<_>>();
+
+                let strings = pairs
+                    .iter()
+                    .map(|(key, value)| (key.clone(), Value::String(value.clone())))
+                    .collect();
+
+                let error = match serde_json::from_value(Value::Object(strings)) {
+                    Ok(value) => return Ok(value),
+                    Err(error) => error,
+                };
+
+                let numbers = pairs
+                    .into_iter()
+                    .map(|(key, value)| match value.parse::<Number>() {
+                        Ok(number) => (key, Value::Number(number)),
+                        Err(..) => (key, Value::String(value)),
+                    })
+                    .collect();
+
+                serde_json::from_value(Value::Object(numbers)).map_err(|_| error)
+            }
+        }
+    }
+}
@@ -0,0 +1,343 @@
+//! Support for running in the browser through WebAssembly.
+//!
+//! This provides:
+//! * [`FetchRequest`], which executes token requests using the browser
+//!   [`fetch`] API. It is constructed through [`Request::with_fetch`].
+//! * [`current_url`], [`parse_callback`] and [`redirect`], which interact
+//!   with `window.location` to perform the authorization redirect and to
+//!   handle the callback.
+//!
+//! If the `rand` feature is disabled, [`State::new_random`] and
+//! [`PkceCodeVerifierS256::new_random`] use the browser [`crypto`] API
+//! instead. So the default features can be disabled:
+//!
+//! ```toml
+//! async-oauth2 = { version = "0.6", default-features = false, features = ["wasm"] }
+//! ```
+//!
+//! The following performs the [Authorization Code Grant] with PKCE as a
+//! public client. Since the page is reloaded by the redirect, the state and
+//! code verifier have to be stored somewhere in the meantime, such as in
+//! `sessionStorage`.
+//!
+//! ```no_run
+//! use oauth2::{wasm, Client, PkceCodeVerifierS256, StandardToken, State};
+//! use url::Url;
+//!
+//! # fn save(state: &State, code_verifier: &PkceCodeVerifierS256) { todo!() }
+//! # fn load() -> Option<(State, PkceCodeVerifierS256)> { todo!() }
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut client = Client::new(
+//!     "client_id",
+//!     Url::parse("http://authorize")?,
+//!     Url::parse("http://token")?
+//! );
+//!
+//! client.set_redirect_url(Url::parse("http://localhost:8080/callback")?);
+//!
+//! let Some((state, code_verifier)) = load() else {
+//!     let state = State::new_random();
+//!     let code_verifier = PkceCodeVerifierS256::new_random();
+//!     save(&state, &code_verifier);
+//!
+//!     let url = client.authorize_request(&state).pkce(&code_verifier).build();
+//!     wasm::redirect(&url)?;
+//!     return Ok(());
+//! };
+//!
+//! let code = wasm::parse_callback(&client, &state)?;
+//!
+//! let token = client
+//!     .exchange_code(code)
+//!     .param("code_verifier", code_verifier.to_string())
+//!     .with_fetch()
+//!     .execute::<StandardToken>()
+//!     .await?;
+//! # Ok(()) }
+//! ```
+//!
+//! [`fetch`]: https://developer.mozilla.org/en-US/docs/Web/API/Fetch_API
+//! [`crypto`]: https://developer.mozilla.org/en-US/docs/Web/API/Crypto
+//! [Authorization Code Grant]: https://tools.ietf.org/html/rfc6749#section-4.1
+//! [`State::new_random`]: crate::State::new_random
+//! [`PkceCodeVerifierS256::new_random`]: crate::PkceCodeVerifierS256::new_random
+
+use core::error::Error;
+use core::fmt;
+
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+
+use bytes::Bytes;
+use http::header::{HeaderMap, HeaderName, HeaderValue};
+use http::StatusCode;
+use js_sys::{Reflect, Uint8Array};
+use serde::Deserialize;
+use url::Url;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{AbortSignal, Headers, RequestInit, Response, Window, WorkerGlobalScope};
+
+use crate::transport::parse_response;
+use crate::{
+    AuthorizationCode, CallbackError, CallbackErrorKind, Client, ExecuteError, ExecuteErrorKind,
+    Request, State,
+};
+
+/// A request which is executed using the browser [`fetch`] API.
+///
+/// [`fetch`]: https://developer.mozilla.org/en-US/docs/Web/API/Fetch_API
+#[derive(Clone)]
+pub struct FetchRequest<'a> {
+    request: Request<'a>,
+}
+
+impl<'a> FetchRequest<'a> {
+    pub(crate) fn new(request: Request<'a>) -> Self {
+        Self { request }
+    }
+
+    /// Execute the token request.
+    ///
+    /// The request is identical to the one sent by the other transports, and
+    /// errors are classified the same way. Timeouts configured through
+    /// [`Client::set_timeout`] or [`Request::timeout`] are enforced using an
+    /// [`AbortSignal`].
+    ///
+    /// [`AbortSignal`]: https://developer.mozilla.org/en-US/docs/Web/API/AbortSignal
+    pub async fn execute<T>(self) -> Result<T, ExecuteError>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let (headers, body) = self.request.encode()?;
+
+        let init = RequestInit::new();
+        init.set_method("POST");
+
+        let request_headers = Headers::new().map_err(ExecuteErrorKind::fetch_error)?;
+
+        for (name, value) in &headers {
+            let Ok(value) = value.to_str() else {
+                continue;
+            };
+
+            request_headers
+                .append(name.as_str(), value)
+                .map_err(ExecuteErrorKind::fetch_error)?;
+        }
+
+        init.set_headers(&request_headers);
+        init.set_body(&Uint8Array::from(&body[..]));
+
+        if let Some(timeout) = self.request.timeout {
+            let millis = u32::try_from(timeout.as_millis()).unwrap_or(u32::MAX);
+            init.set_signal(Some(&AbortSignal::timeout_with_u32(millis)));
+        }
+
+        let request =
+            web_sys::Request::new_with_str_and_init(self.request.token_url.as_str(), &init)
+                .map_err(ExecuteErrorKind::fetch_error)?;
+
+        let response = JsFuture::from(fetch(&request)?)
+            .await
+            .map_err(ExecuteErrorKind::fetch_error)?
+            .dyn_into::<Response>()
+            .map_err(ExecuteErrorKind::fetch_error)?;
+
+        let Ok(status) = StatusCode::from_u16(response.status()) else {
+            return Err(ExecuteError::from(ExecuteErrorKind::FetchError {
+                error: JsError::new("response has an invalid status code"),
+            }));
+        };
+
+        let headers = response_headers(&response.headers());
+
+        let body = response
+            .array_buffer()
+            .map_err(ExecuteErrorKind::fetch_bytes_error)?;
+
+        let body = JsFuture::from(body)
+            .await
+            .map_err(ExecuteErrorKind::fetch_bytes_error)?;
+
+        let body = Bytes::from(Uint8Array::new(&body).to_vec());
+        parse_response(status, headers, body, self.request.detect_success_errors)
+    }
+}
+
+/// Start a request using the `fetch` function of the global scope, which is
+/// either a window or a worker.
+fn fetch(request: &web_sys::Request) -> Result<js_sys::Promise, ExecuteError> {
+    let global = js_sys::global();
+
+    if let Some(window) = global.dyn_ref::<Window>() {
+        return Ok(window.fetch_with_request(request));
+    }
+
+    if let Some(worker) = global.dyn_ref::<WorkerGlobalScope>() {
+        return Ok(worker.fetch_with_request(request));
+    }
+
+    Err(ExecuteError::from(ExecuteErrorKind::FetchError {
+        error: JsError::new("fetch is not available in the global scope"),
+    }))
+}
+
+/// Convert the headers of a response.
+fn response_headers(headers: &Headers) -> HeaderMap {
+    let mut map = HeaderMap::new();
+
+    for entry in headers.entries() {
+        let Ok(entry) = entry else {
+            continue;
+        };
+
+        let entry = js_sys::Array::from(&entry);
+
+        let (Some(name), Some(value)) = (entry.get(0).as_string(), entry.get(1).as_string()) else {
+            continue;
+        };
+
+        let (Ok(name), Ok(value)) = (HeaderName::try_from(name), HeaderValue::try_from(value))
+        else {
+            continue;
+        };
+
+        map.append(name, value);
+    }
+
+    map
+}
+
+/// Get the URL of the current page.
+///
+/// Returns `None` if not running in a window.
+pub fn current_url() -> Option<Url> {
+    let window = web_sys::window()?;
+    let href = window.location().href().ok()?;
+    Url::parse(&href).ok()
+}
+
+/// Parse the callback from the authorization server from the URL of the
+/// current page.
+///
+/// See [`Client::parse_callback`].
+pub fn parse_callback(client: &Client, state: &State) -> Result<AuthorizationCode, CallbackError> {
+    let Some(url) = current_url() else {
+        return Err(CallbackError::from(CallbackErrorKind::NoCurrentUrl));
+    };
+
+    client.parse_callback(&url, state)
+}
+
+/// Navigate the current window to the given URL, such as the URL returned by
+/// [`Client::authorize_url`].
+pub fn redirect(url: &Url) -> Result<(), JsError> {
+    let Some(window) = web_sys::window() else {
+        return Err(JsError::new("not running in a window"));
+    };
+
+    window
+        .location()
+        .set_href(url.as_str())
+        .map_err(JsError::from)
+}
+
+/// Fill the given buffer using the `crypto` object of the global scope.
+///
+/// # Panics
+///
+/// Panics if no `crypto` object is available, since there is no other secure
+/// source of randomness.
+#[cfg(not(feature = "rand"))]
+pub(crate) fn fill_random(bytes: &mut [u8]) {
+    let global = js_sys::global();
+
+    let crypto = if let Some(window) = global.dyn_ref::<Window>() {
+        window.crypto()
+    } else if let Some(worker) = global.dyn_ref::<WorkerGlobalScope>() {
+        worker.crypto()
+    } else {
+        Err(JsValue::UNDEFINED)
+    };
+
+    let crypto = crypto.expect("crypto is not available in the global scope");
+
+    // Browsers limit the number of bytes generated per call.
+    for chunk in bytes.chunks_mut(65536) {
+        crypto
+            .get_random_values_with_u8_array(chunk)
+            .expect("generating random bytes failed");
+    }
+}
+
+/// An error raised by a JavaScript API.
+#[derive(Debug, Clone)]
+pub struct JsError {
+    message: String,
+}
+
+impl JsError {
+    fn new(message: &str) -> Self {
+        Self {
+            message: message.to_owned(),
+        }
+    }
+
+    /// Test if the error was raised due to an [`AbortSignal`] timing out.
+    ///
+    /// [`AbortSignal`]: https://developer.mozilla.org/en-US/docs/Web/API/AbortSignal
+    fn is_timeout(value: &JsValue) -> bool {
+        Reflect::get(value, &JsValue::from_str("name"))
+            .ok()
+            .and_then(|name| name.as_string())
+            .is_some_and(|name| name == "TimeoutError")
+    }
+}
+
+impl From<JsValue> for JsError {
+    fn from(value: JsValue) -> Self {
+        let message = if let Some(error) = value.dyn_ref::<js_sys::Error>() {
+            String::from(error.message())
+        } else if let Some(message) = value.as_string() {
+            message
+        } else {
+            String::from("unknown JavaScript error")
+        };
+
+        Self { message }
+    }
+}
+
+impl fmt::Display for JsError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.message.fmt(f)
+    }
+}
+
+impl Error for JsError {}
+
+impl ExecuteErrorKind {
+    /// Classify an error raised while sending a request.
+    fn fetch_error(value: JsValue) -> Self {
+        if JsError::is_timeout(&value) {
+            return ExecuteErrorKind::Timeout;
+        }
+
+        ExecuteErrorKind::FetchError {
+            error: JsError::from(value),
+        }
+    }
+
+    /// Classify an error raised while reading a response.
+    fn fetch_bytes_error(value: JsValue) -> Self {
+        if JsError::is_timeout(&value) {
+            return ExecuteErrorKind::Timeout;
+        }
+
+        ExecuteErrorKind::FetchBytesError {
+            error: JsError::from(value),
+        }
+    }
+}
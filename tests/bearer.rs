@@ -0,0 +1,140 @@
+//! Tests for attaching access tokens to requests through `Bearer`.
+
+use bytes::Bytes;
+use oauth2::{Bearer, Client, StandardToken, StrictViolation};
+use url::Url;
+
+fn token(token_type: &str) -> StandardToken {
+    let json = format!(r#"{{"access_token":"a+b/c=","token_type":"{token_type}"}}"#);
+    serde_json::from_str(&json).unwrap()
+}
+
+fn client(strict: bool) -> Client {
+    let mut client = Client::new(
+        "client_id",
+        Url::parse("http://authorize").unwrap(),
+        Url::parse("http://token").unwrap(),
+    );
+
+    client.set_strict(strict);
+    client
+}
+
+#[test]
+fn header_only_for_bearer_tokens() {
+    for token_type in ["bearer", "Bearer"] {
+        let token = token(token_type);
+        let mut request = http::Request::get("https://api/").body(()).unwrap();
+        Bearer::new(&token).apply_header(&mut request).unwrap();
+
+        let value = &request.headers()[http::header::AUTHORIZATION];
+        assert_eq!(value, "Bearer a+b/c=");
+        assert!(value.is_sensitive());
+    }
+
+    // DPoP tokens require a proof and MAC tokens a signature, which can't be
+    // provided through the header alone.
+    for token_type in ["N_A", "DPoP", "mac", "Custom"] {
+        let token = token(token_type);
+        let mut request = http::Request::get("https://api/").body(()).unwrap();
+        assert!(Bearer::new(&token).apply_header(&mut request).is_err());
+        assert!(request.headers().is_empty());
+    }
+}
+
+#[test]
+fn debug_is_redacted() {
+    let token = token("bearer");
+    let bearer = Bearer::new(&token);
+    assert!(!format!("{bearer:?}").contains("a+b/c="));
+
+    let mut request = http::Request::get("https://api/").body(()).unwrap();
+    bearer.apply_header(&mut request).unwrap();
+    assert!(!format!("{request:?}").contains("a+b/c="));
+}
+
+#[test]
+fn form_body() {
+    let token = token("bearer");
+
+    let mut request = http::Request::post("https://api/")
+        .body(Bytes::new())
+        .unwrap();
+
+    Bearer::new(&token).apply_form(&mut request).unwrap();
+
+    assert_eq!(request.body(), "access_token=a%2Bb%2Fc%3D");
+    assert_eq!(
+        request.headers()[http::header::CONTENT_TYPE],
+        "application/x-www-form-urlencoded"
+    );
+}
+
+#[test]
+fn form_body_rejected() {
+    let token = token("bearer");
+
+    let mut request = http::Request::get("https://api/").body(Vec::new()).unwrap();
+    assert!(Bearer::new(&token).apply_form(&mut request).is_err());
+
+    let mut request = http::Request::post("https://api/")
+        .header(http::header::CONTENT_TYPE, "application/json")
+        .body(b"{}".to_vec())
+        .unwrap();
+
+    assert!(Bearer::new(&token).apply_form(&mut request).is_err());
+    assert_eq!(request.body(), b"{}");
+
+    let token = self::token("DPoP");
+    let mut request = http::Request::post("https://api/")
+        .body(Vec::new())
+        .unwrap();
+    assert!(Bearer::new(&token).apply_form(&mut request).is_err());
+}
+
+#[test]
+fn query() {
+    let token = token("bearer");
+
+    let mut request = http::Request::get("https://api").body(()).unwrap();
+
+    client(false)
+        .bearer(&token)
+        .allow_query()
+        .apply_query(&mut request)
+        .unwrap();
+
+    assert_eq!(request.uri(), "https://api/?access_token=a%2Bb%2Fc%3D");
+    assert_eq!(request.headers()[http::header::CACHE_CONTROL], "no-store");
+
+    let mut request = http::Request::get("/path?").body(()).unwrap();
+    Bearer::new(&token)
+        .allow_query()
+        .apply_query(&mut request)
+        .unwrap();
+
+    assert_eq!(request.uri(), "/path?access_token=a%2Bb%2Fc%3D");
+}
+
+#[test]
+fn query_rejected() {
+    let token = token("bearer");
+    let mut request = http::Request::get("https://api/").body(()).unwrap();
+
+    let error = Bearer::new(&token).apply_query(&mut request).unwrap_err();
+    assert_eq!(error.strict_violation(), None);
+
+    let error = client(true)
+        .bearer(&token)
+        .allow_query()
+        .apply_query(&mut request)
+        .unwrap_err();
+
+    assert_eq!(
+        error.strict_violation(),
+        Some(StrictViolation::BearerTokenInQuery)
+    );
+
+    assert_eq!(request.uri(), "https://api/");
+    assert!(request.headers().is_empty());
+}
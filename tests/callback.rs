@@ -0,0 +1,129 @@
+//! Tests for parsing the callback from the authorization server, as used by
+//! the browser helpers in the `wasm` module.
+
+use oauth2::{Client, ErrorField, State};
+use url::Url;
+
+fn client() -> Client {
+    Client::new(
+        "client_id",
+        Url::parse("http://authorize").unwrap(),
+        Url::parse("http://token").unwrap(),
+    )
+}
+
+fn state() -> State {
+    State::from_random([42; 16])
+}
+
+#[test]
+fn code_in_query() {
+    let state = state();
+    let url = format!("http://redirect?code=the-code&state={}", state.to_base64());
+    let url = Url::parse(&url).unwrap();
+
+    let code = client().parse_callback(&url, &state).unwrap();
+    assert_eq!(&*code, "the-code");
+}
+
+#[test]
+fn code_in_fragment() {
+    let state = state();
+    let url = format!(
+        "http://redirect#state={}&code=the%20code",
+        state.to_base64()
+    );
+    let url = Url::parse(&url).unwrap();
+
+    let code = client().parse_callback(&url, &state).unwrap();
+    assert_eq!(&*code, "the code");
+}
+
+#[test]
+fn query_takes_precedence_over_fragment() {
+    let state = state();
+    let url = format!(
+        "http://redirect?code=query&state={0}#code=fragment&state={0}",
+        state.to_base64()
+    );
+    let url = Url::parse(&url).unwrap();
+
+    let code = client().parse_callback(&url, &state).unwrap();
+    assert_eq!(&*code, "query");
+}
+
+#[test]
+fn error_response() {
+    let state = state();
+    let url = format!(
+        "http://redirect?error=access_denied&error_description=denied%20by%20user&state={}",
+        state.to_base64()
+    );
+    let url = Url::parse(&url).unwrap();
+
+    let error = client().parse_callback(&url, &state).unwrap_err();
+    let response = error.error_response().unwrap();
+    assert_eq!(response.error, ErrorField::AccessDenied);
+    assert_eq!(
+        response.error_description.as_deref(),
+        Some("denied by user")
+    );
+    assert!(!error.is_state_mismatch());
+}
+
+#[test]
+fn error_response_state_mismatch() {
+    let url = format!(
+        "http://redirect?error=access_denied&state={}",
+        State::from_random([1; 16]).to_base64()
+    );
+    let url = Url::parse(&url).unwrap();
+
+    let error = client().parse_callback(&url, &state()).unwrap_err();
+    assert!(error.is_state_mismatch());
+    assert!(error.error_response().is_none());
+}
+
+#[test]
+fn error_response_without_state() {
+    let url = Url::parse("http://redirect?error=access_denied").unwrap();
+
+    let error = client().parse_callback(&url, &state()).unwrap_err();
+    assert_eq!(
+        error.error_response().unwrap().error,
+        ErrorField::AccessDenied
+    );
+    assert!(!error.is_state_mismatch());
+}
+
+#[test]
+fn missing_state() {
+    let url = Url::parse("http://redirect?code=the-code").unwrap();
+
+    let error = client().parse_callback(&url, &state()).unwrap_err();
+    assert!(error.error_response().is_none());
+    assert!(!error.is_state_mismatch());
+}
+
+#[test]
+fn state_mismatch() {
+    let url = format!(
+        "http://redirect?code=the-code&state={}",
+        State::from_random([1; 16]).to_base64()
+    );
+    let url = Url::parse(&url).unwrap();
+
+    let error = client().parse_callback(&url, &state()).unwrap_err();
+    assert!(error.is_state_mismatch());
+}
+
+#[test]
+fn missing_code() {
+    let state = state();
+    let url = format!("http://redirect?state={}", state.to_base64());
+    let url = Url::parse(&url).unwrap();
+
+    let error = client().parse_callback(&url, &state).unwrap_err();
+    assert!(error.error_response().is_none());
+    assert!(!error.is_state_mismatch());
+}
@@ -0,0 +1,311 @@
+//! Tests for the token manager against the mock authorization server.
+
+use std::future::{poll_fn, Future};
+use std::pin::pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::task::Poll;
+use std::time::Duration;
+
+use http::StatusCode;
+use oauth2::mock::{Endpoint, MockResponse, MockServer};
+use oauth2::{
+    Client, ErrorField, ErrorKind, RefreshToken, StandardToken, Timestamp, Token, TokenManager,
+};
+use serde_json::json;
+
+async fn setup() -> (MockServer, Client) {
+    let server = MockServer::start().await.unwrap();
+    server.register_client("client id", Some("client:secret"));
+
+    let mut client = server.client("client id");
+    client.set_client_secret("client:secret");
+    (server, client)
+}
+
+/// Obtain a token with a refresh token through the password grant.
+async fn password_token(client: &Client) -> StandardToken {
+    client
+        .exchange_password("user", "pass")
+        .with_reqwest_client(&reqwest::Client::new())
+        .execute()
+        .await
+        .unwrap()
+}
+
+/// Replace the clock of the manager with one which only advances when the
+/// returned number of seconds since the Unix epoch is changed.
+fn manual_clock(manager: &TokenManager) -> Arc<AtomicU64> {
+    let now = Arc::new(AtomicU64::new(1_000_000));
+    let clock = now.clone();
+    manager.set_clock(move || Timestamp::from_unix_secs(clock.load(Ordering::SeqCst)));
+    now
+}
+
+/// The grant types of all requests received by the token endpoint.
+fn grant_types(server: &MockServer) -> Vec<String> {
+    server
+        .requests_to(Endpoint::Token)
+        .iter()
+        .map(|request| request.form("grant_type").unwrap().to_owned())
+        .collect()
+}
+
+#[tokio::test]
+async fn refresh_before_expiry() {
+    let (server, client) = setup().await;
+    let token = password_token(&client).await;
+
+    let manager = TokenManager::new(client, reqwest::Client::new());
+
+    let now = manual_clock(&manager);
+    manager.set_token(token.clone());
+
+    assert_eq!(manager.access_token().await.unwrap(), *token.access_token());
+
+    // Still outside of the default expiry skew of 30 seconds.
+    now.fetch_add(3569, Ordering::SeqCst);
+    assert_eq!(manager.access_token().await.unwrap(), *token.access_token());
+
+    now.fetch_add(1, Ordering::SeqCst);
+    let refreshed = manager.access_token().await.unwrap();
+    assert_ne!(refreshed, *token.access_token());
+    assert_eq!(manager.access_token().await.unwrap(), refreshed);
+
+    let requests = server.requests_to(Endpoint::Token);
+    assert_eq!(requests.len(), 2);
+    assert_eq!(
+        requests[1].form("refresh_token"),
+        token.refresh_token().map(|refresh_token| &**refresh_token)
+    );
+}
+
+#[tokio::test]
+async fn refresh_token_rotation() {
+    let (server, client) = setup().await;
+    let token = password_token(&client).await;
+
+    // The mock server doesn't rotate refresh tokens by itself.
+    server.enqueue(
+        Endpoint::Token,
+        MockResponse::json(
+            StatusCode::OK,
+            &json!({
+                "access_token": "rotated",
+                "token_type": "bearer",
+                "refresh_token": "rotated refresh token",
+                "expires_in": 3600,
+            }),
+        ),
+    );
+
+    server.enqueue(
+        Endpoint::Token,
+        MockResponse::json(
+            StatusCode::OK,
+            &json!({"access_token": "second", "token_type": "bearer"}),
+        ),
+    );
+
+    let manager = TokenManager::new(client, reqwest::Client::new());
+    let now = manual_clock(&manager);
+    manager.set_token(token.clone());
+
+    now.fetch_add(3600, Ordering::SeqCst);
+    assert_eq!(&*manager.access_token().await.unwrap(), "rotated");
+
+    now.fetch_add(3600, Ordering::SeqCst);
+    assert_eq!(&*manager.access_token().await.unwrap(), "second");
+
+    let requests = server.requests_to(Endpoint::Token);
+    assert_eq!(requests.len(), 3);
+    assert_eq!(
+        requests[1].form("refresh_token"),
+        token.refresh_token().map(|refresh_token| &**refresh_token)
+    );
+    assert_eq!(
+        requests[2].form("refresh_token"),
+        Some("rotated refresh token")
+    );
+}
+
+#[tokio::test]
+async fn single_flight() {
+    let (server, client) = setup().await;
+    let manager = TokenManager::new(client, reqwest::Client::new());
+    let clone = manager.clone();
+
+    let (a, b, c) = tokio::join!(
+        manager.access_token(),
+        manager.access_token(),
+        clone.access_token(),
+    );
+
+    assert_eq!(&*a.unwrap(), "mock-access-token-1");
+    assert_eq!(&*b.unwrap(), "mock-access-token-1");
+    assert_eq!(&*c.unwrap(), "mock-access-token-1");
+    assert_eq!(server.requests_to(Endpoint::Token).len(), 1);
+}
+
+#[tokio::test]
+async fn cancellation() {
+    let (_server, client) = setup().await;
+    let manager = TokenManager::new(client, reqwest::Client::new());
+
+    {
+        let mut future = pin!(manager.access_token());
+
+        poll_fn(|cx| {
+            assert!(future.as_mut().poll(cx).is_pending());
+            Poll::Ready(())
+        })
+        .await;
+    }
+
+    // The cancelled refresh doesn't block subsequent callers.
+    let access_token = tokio::time::timeout(Duration::from_secs(5), manager.access_token())
+        .await
+        .unwrap();
+
+    assert!(access_token.is_ok());
+}
+
+#[tokio::test]
+async fn cancelled_refresh() {
+    let (server, client) = setup().await;
+    let token = password_token(&client).await;
+    let refresh_token = token.refresh_token().unwrap().clone();
+
+    let manager = TokenManager::new(client.clone(), reqwest::Client::new());
+    let now = manual_clock(&manager);
+    manager.set_token(token);
+    now.fetch_add(3600, Ordering::SeqCst);
+
+    server.enqueue(
+        Endpoint::Token,
+        MockResponse::error(StatusCode::SERVICE_UNAVAILABLE, ErrorField::ServerError)
+            .delay(Duration::from_secs(10)),
+    );
+
+    // Cancel the refresh once it has reached the server.
+    let refresh = manager.access_token();
+
+    let wait = async {
+        while server.requests_to(Endpoint::Token).len() < 2 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    };
+
+    tokio::select! {
+        _ = refresh => panic!("refresh completed"),
+        _ = wait => {}
+    }
+
+    // The refresh token might have been rotated, so it's never sent again.
+    let error = manager.access_token().await.unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::AuthorizationRequired);
+    assert_eq!(grant_types(&server), ["password", "refresh_token"]);
+
+    let sent = server
+        .requests_to(Endpoint::Token)
+        .iter()
+        .filter(|request| request.form("refresh_token") == Some(&*refresh_token))
+        .count();
+    assert_eq!(sent, 1);
+
+    // Providing a new token resumes refreshing.
+    manager.set_token(password_token(&client).await);
+    assert!(manager.access_token().await.is_ok());
+}
+
+#[tokio::test]
+async fn refresh_timeout_client_credentials() {
+    let (server, mut client) = setup().await;
+    client.set_timeout(Duration::from_millis(100));
+
+    server.enqueue(
+        Endpoint::Token,
+        MockResponse::json(
+            StatusCode::OK,
+            &json!({
+                "access_token": "expiring",
+                "token_type": "bearer",
+                "refresh_token": "rotated",
+                "expires_in": 1,
+            }),
+        ),
+    );
+
+    server.enqueue(
+        Endpoint::Token,
+        MockResponse::error(StatusCode::SERVICE_UNAVAILABLE, ErrorField::ServerError)
+            .delay(Duration::from_secs(10)),
+    );
+
+    let manager = TokenManager::new(client, reqwest::Client::new());
+    assert_eq!(&*manager.access_token().await.unwrap(), "expiring");
+
+    // A refresh token issued for the client credentials is replaced using
+    // the client credentials when its refresh times out.
+    assert_eq!(
+        &*manager.access_token().await.unwrap(),
+        "mock-access-token-1"
+    );
+    assert_eq!(
+        grant_types(&server),
+        ["client_credentials", "refresh_token", "client_credentials"]
+    );
+}
+
+#[tokio::test]
+async fn invalid_grant() {
+    let (server, client) = setup().await;
+    let manager = TokenManager::new(client, reqwest::Client::new());
+
+    // A rejected refresh token which was provided by the caller is
+    // discarded, but the error is returned.
+    manager.set_refresh_token(RefreshToken::from("revoked".to_owned()));
+    assert!(manager.access_token().await.unwrap_err().is_invalid_grant());
+    assert_eq!(grant_types(&server), ["refresh_token"]);
+
+    assert_eq!(
+        &*manager.access_token().await.unwrap(),
+        "mock-access-token-1"
+    );
+    assert_eq!(
+        grant_types(&server),
+        ["refresh_token", "client_credentials"]
+    );
+}
+
+#[tokio::test]
+async fn invalid_grant_client_credentials() {
+    let (server, client) = setup().await;
+
+    server.enqueue(
+        Endpoint::Token,
+        MockResponse::json(
+            StatusCode::OK,
+            &json!({
+                "access_token": "expiring",
+                "token_type": "bearer",
+                "refresh_token": "revoked",
+                "expires_in": 1,
+            }),
+        ),
+    );
+
+    let manager = TokenManager::new(client, reqwest::Client::new());
+    assert_eq!(&*manager.access_token().await.unwrap(), "expiring");
+
+    // A rejected refresh token which was issued for the client credentials
+    // is replaced using the client credentials.
+    assert_eq!(
+        &*manager.access_token().await.unwrap(),
+        "mock-access-token-1"
+    );
+    assert_eq!(
+        grant_types(&server),
+        ["client_credentials", "refresh_token", "client_credentials"]
+    );
+}
@@ -0,0 +1,505 @@
+//! Tests for the grants supported by the client against the mock
+//! authorization server.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use http::StatusCode;
+use oauth2::mock::{Endpoint, MockResponse, MockServer};
+use oauth2::{
+    AuthType, AuthenticationHint, BackchannelAuthenticationResponse, Client, ErrorField, ErrorKind,
+    ExecuteError, PkceCodeVerifierS256, RefreshToken, StandardToken, State, Token,
+};
+use url::Url;
+
+async fn setup() -> (MockServer, Client) {
+    let server = MockServer::start().await.unwrap();
+    server.register_client("client id", Some("client:secret"));
+
+    let mut client = server.client("client id");
+    client.set_client_secret("client:secret");
+    client.set_redirect_url(Url::parse("http://localhost/callback").unwrap());
+    client.add_scope("read");
+    (server, client)
+}
+
+/// Send a form encoded request authenticated as the registered client.
+async fn post(server: &MockServer, endpoint: Endpoint, token: &str) -> reqwest::Response {
+    let body = url::form_urlencoded::Serializer::new(String::new())
+        .append_pair("token", token)
+        .finish();
+
+    reqwest::Client::new()
+        .post(server.url(endpoint))
+        .basic_auth("client%20id", Some("client%3Asecret"))
+        .header(
+            http::header::CONTENT_TYPE,
+            "application/x-www-form-urlencoded",
+        )
+        .body(body)
+        .send()
+        .await
+        .unwrap()
+}
+
+/// Execute a token request through reqwest.
+async fn execute(
+    http: &reqwest::Client,
+    request: oauth2::Request<'_>,
+) -> Result<StandardToken, ExecuteError> {
+    request.with_reqwest_client(http).execute().await
+}
+
+async fn introspect(server: &MockServer, token: &str) -> serde_json::Value {
+    let response = post(server, Endpoint::Introspection, token).await;
+    serde_json::from_slice(&response.bytes().await.unwrap()).unwrap()
+}
+
+#[tokio::test]
+async fn authorization_code_with_pkce() {
+    let (server, client) = setup().await;
+    let http = reqwest::Client::new();
+
+    let state = State::from_random([42; 16]);
+    let code_verifier = PkceCodeVerifierS256::new_random();
+
+    let url = client
+        .authorize_request(&state)
+        .pkce(&code_verifier)
+        .build();
+
+    let callback = server.authorize(&url).unwrap();
+    let code = client.parse_callback(&callback, &state).unwrap();
+
+    let exchange = client
+        .exchange_code(code)
+        .param("code_verifier", code_verifier.to_string());
+
+    let token = exchange
+        .clone()
+        .with_reqwest_client(&http)
+        .execute::<StandardToken>()
+        .await
+        .unwrap();
+
+    assert!(token.refresh_token().is_some());
+    assert_eq!(token.scopes().unwrap().iter().count(), 1);
+
+    let request = server.requests_to(Endpoint::Token).pop().unwrap();
+    assert_eq!(request.form("grant_type"), Some("authorization_code"));
+    assert_eq!(
+        request.basic_auth(),
+        Some(("client id".to_owned(), "client:secret".to_owned()))
+    );
+
+    // Authorization codes can only be used once.
+    let error = exchange
+        .with_reqwest_client(&http)
+        .execute::<StandardToken>()
+        .await
+        .unwrap_err();
+
+    assert!(error.is_invalid_grant());
+}
+
+#[tokio::test]
+async fn authorization_code_pkce_mismatch() {
+    let (server, client) = setup().await;
+    let http = reqwest::Client::new();
+
+    let state = State::from_random([42; 16]);
+
+    let url = client
+        .authorize_request(&state)
+        .pkce(&PkceCodeVerifierS256::new_random())
+        .build();
+
+    let code = client
+        .parse_callback(&server.authorize(&url).unwrap(), &state)
+        .unwrap();
+
+    let error = client
+        .exchange_code(code)
+        .param(
+            "code_verifier",
+            PkceCodeVerifierS256::new_random().to_string(),
+        )
+        .with_reqwest_client(&http)
+        .execute::<StandardToken>()
+        .await
+        .unwrap_err();
+
+    assert!(error.is_invalid_grant());
+}
+
+#[tokio::test]
+async fn implicit() {
+    let (server, client) = setup().await;
+    let state = State::from_random([42; 16]);
+
+    let callback = server
+        .authorize(&client.authorize_url_implicit(&state))
+        .unwrap();
+
+    let params = url::form_urlencoded::parse(callback.fragment().unwrap().as_bytes())
+        .into_owned()
+        .collect::<Vec<_>>();
+
+    let access_token = params
+        .iter()
+        .find(|(key, _)| key == "access_token")
+        .map(|(_, value)| value.as_str())
+        .unwrap();
+
+    assert!(params.contains(&("state".to_owned(), state.to_base64())));
+    assert_eq!(introspect(&server, access_token).await["active"], true);
+}
+
+#[tokio::test]
+async fn authorize_errors() {
+    let (server, client) = setup().await;
+    let state = State::from_random([42; 16]);
+
+    let mut url = client.authorize_url(&state);
+
+    let pairs = url
+        .query_pairs()
+        .into_owned()
+        .map(|(key, value)| match key.as_str() {
+            "response_type" => (key, "unknown".to_owned()),
+            _ => (key, value),
+        })
+        .collect::<Vec<_>>();
+
+    url.query_pairs_mut().clear().extend_pairs(pairs);
+
+    let error = client
+        .parse_callback(&server.authorize(&url).unwrap(), &state)
+        .unwrap_err();
+
+    assert_eq!(
+        error.error_response().unwrap().error,
+        ErrorField::Other("unsupported_response_type".to_owned())
+    );
+
+    let mut client = server.client("client id");
+    client.add_scope("read");
+    assert!(server.authorize(&client.authorize_url(&state)).is_none());
+}
+
+#[tokio::test]
+async fn password_and_refresh() {
+    let (_server, client) = setup().await;
+    let http = reqwest::Client::new();
+
+    let token = client
+        .exchange_password("user", "pass")
+        .with_reqwest_client(&http)
+        .execute::<StandardToken>()
+        .await
+        .unwrap();
+
+    let refresh_token = token.refresh_token().unwrap();
+
+    let refreshed = client
+        .exchange_refresh_token(refresh_token)
+        .with_reqwest_client(&http)
+        .execute::<StandardToken>()
+        .await
+        .unwrap();
+
+    assert_ne!(refreshed.access_token(), token.access_token());
+
+    let error = client
+        .exchange_refresh_token(&RefreshToken::from("unknown".to_owned()))
+        .with_reqwest_client(&http)
+        .execute::<StandardToken>()
+        .await
+        .unwrap_err();
+
+    assert!(error.is_invalid_grant());
+}
+
+#[tokio::test]
+async fn client_credentials_in_body() {
+    let (server, mut client) = setup().await;
+    let http = reqwest::Client::new();
+
+    client.set_auth_type(AuthType::RequestBody);
+
+    let token = client
+        .exchange_client_credentials()
+        .with_reqwest_client(&http)
+        .execute::<StandardToken>()
+        .await
+        .unwrap();
+
+    assert!(token.refresh_token().is_none());
+
+    let request = server.requests().pop().unwrap();
+    assert_eq!(request.basic_auth(), None);
+    assert_eq!(request.form("client_id"), Some("client id"));
+    assert_eq!(request.form("client_secret"), Some("client:secret"));
+}
+
+#[tokio::test]
+async fn public_client_basic_auth() {
+    let server = MockServer::start().await.unwrap();
+    server.register_client("public", None);
+
+    let client = server.client("public");
+    let http = reqwest::Client::new();
+
+    let token = client
+        .exchange_password("user", "pass")
+        .with_reqwest_client(&http)
+        .execute::<StandardToken>()
+        .await
+        .unwrap();
+
+    assert!(token.refresh_token().is_some());
+
+    let request = server.requests().pop().unwrap();
+    assert_eq!(
+        request.basic_auth(),
+        Some(("public".to_owned(), String::new()))
+    );
+}
+
+#[tokio::test]
+async fn invalid_client() {
+    let (server, mut client) = setup().await;
+    let http = reqwest::Client::new();
+
+    client.set_client_secret("wrong");
+
+    let error = client
+        .exchange_client_credentials()
+        .with_reqwest_client(&http)
+        .execute::<StandardToken>()
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.status(), Some(StatusCode::UNAUTHORIZED));
+    assert_eq!(
+        error.error_response().unwrap().error,
+        ErrorField::InvalidClient
+    );
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn saml2_bearer() {
+    let (server, client) = setup().await;
+    let http = reqwest::Client::new();
+
+    client
+        .exchange_saml2_bearer("<assertion/>")
+        .with_reqwest_client(&http)
+        .execute::<StandardToken>()
+        .await
+        .unwrap();
+
+    let request = server.requests().pop().unwrap();
+    assert_eq!(
+        request.form("grant_type"),
+        Some("urn:ietf:params:oauth:grant-type:saml2-bearer")
+    );
+    assert!(request.form("assertion").is_some());
+}
+
+#[tokio::test]
+async fn backchannel() {
+    let (server, client) = setup().await;
+    let http = reqwest::Client::new();
+
+    let response = client
+        .backchannel_authentication(AuthenticationHint::LoginHint("user".to_owned()))
+        .unwrap()
+        .with_reqwest_client(&http)
+        .execute::<BackchannelAuthenticationResponse>()
+        .await
+        .unwrap();
+
+    let token = client
+        .poll_backchannel_authentication(&response, |request| execute(&http, request), |_| async {})
+        .await
+        .unwrap();
+
+    assert!(token.refresh_token().is_some());
+
+    let request = server
+        .requests_to(Endpoint::BackchannelAuthentication)
+        .pop()
+        .unwrap();
+
+    assert_eq!(request.form("login_hint"), Some("user"));
+}
+
+#[tokio::test]
+async fn backchannel_polling() {
+    let (server, client) = setup().await;
+    let http = reqwest::Client::new();
+
+    let response = client
+        .backchannel_authentication(AuthenticationHint::LoginHint("user".to_owned()))
+        .unwrap()
+        .with_reqwest_client(&http)
+        .execute::<BackchannelAuthenticationResponse>()
+        .await
+        .unwrap();
+
+    server.enqueue(
+        Endpoint::Token,
+        MockResponse::error(StatusCode::BAD_REQUEST, ErrorField::AuthorizationPending),
+    );
+
+    server.enqueue(
+        Endpoint::Token,
+        MockResponse::error(StatusCode::BAD_REQUEST, ErrorField::SlowDown),
+    );
+
+    let sleeps = Mutex::new(Vec::new());
+
+    client
+        .poll_backchannel_authentication(
+            &response,
+            |request| execute(&http, request),
+            |duration| {
+                sleeps.lock().unwrap().push(duration);
+                async {}
+            },
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        sleeps.into_inner().unwrap(),
+        [5, 5, 10].map(Duration::from_secs)
+    );
+    assert_eq!(server.requests_to(Endpoint::Token).len(), 3);
+}
+
+#[tokio::test]
+async fn backchannel_expired() {
+    let (server, client) = setup().await;
+    let http = reqwest::Client::new();
+
+    let response = serde_json::from_str::<BackchannelAuthenticationResponse>(
+        r#"{"auth_req_id":"unknown","expires_in":12}"#,
+    )
+    .unwrap();
+
+    for _ in 0..2 {
+        server.enqueue(
+            Endpoint::Token,
+            MockResponse::error(StatusCode::BAD_REQUEST, ErrorField::AuthorizationPending),
+        );
+    }
+
+    let sleeps = Mutex::new(Vec::new());
+
+    let error = client
+        .poll_backchannel_authentication(
+            &response,
+            |request| execute(&http, request),
+            |duration| {
+                sleeps.lock().unwrap().push(duration);
+                async {}
+            },
+        )
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.kind(), ErrorKind::Expired);
+    assert!(!error.is_timeout());
+    assert_eq!(
+        sleeps.into_inner().unwrap(),
+        [5, 5].map(Duration::from_secs)
+    );
+    assert_eq!(server.requests_to(Endpoint::Token).len(), 2);
+    assert_eq!(server.queued(), 0);
+}
+
+#[tokio::test]
+async fn backchannel_not_configured() {
+    let client = Client::new(
+        "client id",
+        Url::parse("http://authorize").unwrap(),
+        Url::parse("http://token").unwrap(),
+    );
+
+    let Err(error) =
+        client.backchannel_authentication(AuthenticationHint::LoginHint("user".to_owned()))
+    else {
+        panic!("backchannel authentication shouldn't be configured");
+    };
+
+    assert_eq!(
+        error.to_string(),
+        "backchannel authentication endpoint is not configured"
+    );
+}
+
+#[tokio::test]
+async fn revocation_and_introspection() {
+    let (server, client) = setup().await;
+    let http = reqwest::Client::new();
+
+    let token = client
+        .exchange_client_credentials()
+        .with_reqwest_client(&http)
+        .execute::<StandardToken>()
+        .await
+        .unwrap();
+
+    let access_token = &**token.access_token();
+
+    let introspection = introspect(&server, access_token).await;
+    assert_eq!(introspection["active"], true);
+    assert_eq!(introspection["client_id"], "client id");
+    assert_eq!(introspection["scope"], "read");
+
+    let response = post(&server, Endpoint::Revocation, access_token).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(introspect(&server, access_token).await["active"], false);
+    assert_eq!(introspect(&server, "unknown").await["active"], false);
+}
+
+#[tokio::test]
+async fn scripted_responses() {
+    let (server, client) = setup().await;
+    let http = reqwest::Client::new();
+
+    server.enqueue(
+        Endpoint::Token,
+        MockResponse::error(
+            StatusCode::SERVICE_UNAVAILABLE,
+            ErrorField::TemporarilyUnavailable,
+        )
+        .header(
+            http::header::RETRY_AFTER,
+            http::HeaderValue::from_static("3"),
+        ),
+    );
+
+    assert_eq!(server.queued(), 1);
+
+    let request = client
+        .exchange_client_credentials()
+        .with_reqwest_client(&http);
+
+    let error = request
+        .clone()
+        .execute::<StandardToken>()
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.status(), Some(StatusCode::SERVICE_UNAVAILABLE));
+    assert_eq!(error.retry_after(), Some(std::time::Duration::from_secs(3)));
+    assert_eq!(server.queued(), 0);
+
+    request.execute::<StandardToken>().await.unwrap();
+    assert_eq!(server.requests_to(Endpoint::Token).len(), 2);
+}
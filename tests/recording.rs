@@ -0,0 +1,248 @@
+//! Golden-file tests of the wire format of token requests.
+//!
+//! Run with `OAUTH2_UPDATE_GOLDEN=1` to update the files in `tests/golden`.
+
+use std::path::PathBuf;
+
+use http::header::{HeaderValue, CONTENT_TYPE};
+use http::StatusCode;
+use oauth2::recording::{Recorder, Recording, Replay};
+use oauth2::{AuthType, Client, ErrorField, ErrorKind, RefreshToken, StandardToken, Token};
+use serde::Deserialize;
+use url::Url;
+
+fn golden(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("golden")
+        .join(name)
+}
+
+fn client() -> Client {
+    let mut client = Client::new(
+        "client id+1",
+        Url::parse("http://authorize").unwrap(),
+        Url::parse("http://token/oauth?tenant=1").unwrap(),
+    );
+
+    client.set_client_secret("s3cr:t&");
+    client.set_redirect_url(Url::parse("http://localhost/callback?a=b").unwrap());
+    client.add_scope("read");
+    client.add_scope("write:all");
+    client
+}
+
+/// A recorder which replays a successful token response.
+fn recorder() -> Recorder {
+    let recorder = Recorder::new();
+    recorder.replay_file(golden("token.http")).unwrap();
+    recorder
+}
+
+#[test]
+fn client_credentials_basic_auth() {
+    let recorder = recorder();
+
+    let token = client()
+        .exchange_client_credentials()
+        .with_recorder(&recorder)
+        .execute::<StandardToken>()
+        .unwrap();
+
+    assert_eq!(&**token.access_token(), "access_token");
+
+    let recording = recorder.recordings().pop().unwrap();
+    recording.assert_golden(golden("client_credentials_basic_auth.txt"));
+}
+
+#[test]
+fn client_credentials_request_body() {
+    let recorder = recorder();
+    let mut client = client();
+    client.set_auth_type(AuthType::RequestBody);
+
+    client
+        .exchange_client_credentials()
+        .with_recorder(&recorder)
+        .execute::<StandardToken>()
+        .unwrap();
+
+    let recording = recorder.recordings().pop().unwrap();
+    assert!(!recording.body().contains("s3cr"));
+    recording.assert_golden(golden("client_credentials_request_body.txt"));
+}
+
+#[test]
+fn exchange_code() {
+    let recorder = recorder();
+
+    client()
+        .exchange_code("the code")
+        .param("code_verifier", "verifier")
+        .with_recorder(&recorder)
+        .execute::<StandardToken>()
+        .unwrap();
+
+    let recording = recorder.recordings().pop().unwrap();
+    recording.assert_golden(golden("exchange_code.txt"));
+}
+
+#[test]
+fn password_and_refresh_token() {
+    let recorder = recorder();
+    recorder.replay_file(golden("token.http")).unwrap();
+
+    let client = client();
+
+    client
+        .exchange_password("user name", "pass word")
+        .with_recorder(&recorder)
+        .execute::<StandardToken>()
+        .unwrap();
+
+    client
+        .exchange_refresh_token(&RefreshToken::from("refresh token"))
+        .with_recorder(&recorder)
+        .execute::<StandardToken>()
+        .unwrap();
+
+    let recordings = recorder.recordings();
+    assert!(!recordings[0].body().contains("user+name"));
+    recordings[0].assert_golden(golden("password.txt"));
+    recordings[1].assert_golden(golden("refresh_token.txt"));
+}
+
+#[test]
+fn scope_subset() {
+    let recorder = recorder();
+    recorder.replay_file(golden("token.http")).unwrap();
+
+    let client = client();
+
+    client
+        .exchange_refresh_token(&RefreshToken::from("refresh token"))
+        .scopes(["read"])
+        .with_recorder(&recorder)
+        .execute::<StandardToken>()
+        .unwrap();
+
+    client
+        .exchange_client_credentials()
+        .scopes(["write:all"])
+        .with_recorder(&recorder)
+        .execute::<StandardToken>()
+        .unwrap();
+
+    let scope = |recording: &Recording| {
+        url::form_urlencoded::parse(recording.body().as_bytes())
+            .filter(|(key, _)| key == "scope")
+            .map(|(_, value)| value.into_owned())
+            .collect::<Vec<_>>()
+    };
+
+    let recordings = recorder.recordings();
+    assert_eq!(scope(&recordings[0]), ["read"]);
+    assert_eq!(scope(&recordings[1]), ["write:all"]);
+}
+
+#[test]
+fn form_urlencoded_extensions() {
+    #[derive(Deserialize)]
+    struct Extensions {
+        id_token: String,
+        refresh_token_expires_in: u64,
+    }
+
+    let recorder = Recorder::new();
+
+    recorder.replay(
+        Replay::new(StatusCode::OK)
+            .header(
+                CONTENT_TYPE,
+                HeaderValue::from_static("application/x-www-form-urlencoded"),
+            )
+            .body("access_token=token&token_type=bearer&id_token=header.payload.signature&refresh_token_expires_in=7200"),
+    );
+
+    let token = client()
+        .exchange_client_credentials()
+        .with_recorder(&recorder)
+        .execute::<StandardToken<Extensions>>()
+        .unwrap();
+
+    assert_eq!(&**token.access_token(), "token");
+    assert_eq!(token.extensions().id_token, "header.payload.signature");
+    assert_eq!(token.extensions().refresh_token_expires_in, 7200);
+}
+
+#[test]
+fn extension_params_are_redacted() {
+    let recorder = recorder();
+
+    client()
+        .exchange_client_credentials()
+        .param("client_assertion_type", "urn:example:jwt")
+        .param("client_assertion", "header.payload.signature")
+        .with_recorder(&recorder)
+        .execute::<StandardToken>()
+        .unwrap();
+
+    let recording = recorder.recordings().pop().unwrap();
+    assert!(!recording.body().contains("payload"));
+    assert!(!recording.body().contains("urn:example"));
+    assert!(recording
+        .body()
+        .starts_with("grant_type=client_credentials&client_assertion_type=[redacted]&client_assertion=[redacted]&scope=read"));
+}
+
+#[test]
+fn replayed_error_response() {
+    let recorder = Recorder::new();
+    recorder.replay_file(golden("invalid_grant.http")).unwrap();
+
+    let error = client()
+        .exchange_refresh_token(&RefreshToken::from("refresh token"))
+        .with_recorder(&recorder)
+        .execute::<StandardToken>()
+        .unwrap_err();
+
+    assert_eq!(error.status(), Some(StatusCode::BAD_REQUEST));
+    assert_eq!(
+        error.error_response().unwrap().error,
+        ErrorField::InvalidGrant
+    );
+}
+
+#[test]
+fn nothing_to_replay() {
+    let recorder = Recorder::new();
+
+    let error = client()
+        .exchange_client_credentials()
+        .with_recorder(&recorder)
+        .execute::<StandardToken>()
+        .unwrap_err();
+
+    assert_eq!(error.kind(), ErrorKind::Transport);
+    assert_eq!(recorder.recordings().len(), 1);
+}
+
+#[test]
+fn parse_replay() {
+    let replay = Replay::parse("HTTP/1.1 429 Too Many Requests\r\nretry-after: 2\r\n\r\n").unwrap();
+    let recorder = Recorder::new();
+    recorder.replay(replay);
+
+    let error = client()
+        .exchange_client_credentials()
+        .with_recorder(&recorder)
+        .execute::<StandardToken>()
+        .unwrap_err();
+
+    assert_eq!(error.status(), Some(StatusCode::TOO_MANY_REQUESTS));
+    assert_eq!(error.retry_after(), Some(std::time::Duration::from_secs(2)));
+
+    assert!(Replay::parse("").is_err());
+    assert!(Replay::parse("HTTP/1.1 abc\n\n").is_err());
+    assert!(Replay::parse("HTTP/1.1 200 OK\nnot a header\n\n").is_err());
+}
@@ -0,0 +1,107 @@
+//! Tests for the reqwest-middleware integration against the mock
+//! authorization server.
+
+use std::sync::{Arc, Mutex};
+
+use http::header::{AUTHORIZATION, WWW_AUTHENTICATE};
+use http::{Extensions, StatusCode};
+use oauth2::mock::{Endpoint, MockServer};
+use oauth2::reqwest_middleware::BearerAuthMiddleware;
+use oauth2::TokenManager;
+use reqwest_middleware::{ClientBuilder, ClientWithMiddleware, Middleware, Next};
+
+/// A terminal middleware which responds with `401 Unauthorized` without
+/// sending the request, recording the `Authorization` header of every request
+/// it receives.
+#[derive(Clone)]
+struct Unauthorized {
+    www_authenticate: &'static str,
+    received: Arc<Mutex<Vec<String>>>,
+}
+
+#[async_trait::async_trait]
+impl Middleware for Unauthorized {
+    async fn handle(
+        &self,
+        request: reqwest::Request,
+        _: &mut Extensions,
+        _: Next<'_>,
+    ) -> reqwest_middleware::Result<reqwest::Response> {
+        let authorization = request.headers()[AUTHORIZATION].to_str().unwrap();
+        self.received.lock().unwrap().push(authorization.to_owned());
+
+        let response = http::Response::builder()
+            .status(StatusCode::UNAUTHORIZED)
+            .header(WWW_AUTHENTICATE, self.www_authenticate)
+            .body("")
+            .unwrap();
+
+        Ok(reqwest::Response::from(response))
+    }
+}
+
+async fn setup(www_authenticate: &'static str) -> (MockServer, ClientWithMiddleware, Unauthorized) {
+    let server = MockServer::start().await.unwrap();
+    server.register_client("client id", Some("client:secret"));
+
+    let mut client = server.client("client id");
+    client.set_client_secret("client:secret");
+
+    let manager = TokenManager::new(client, reqwest::Client::new());
+
+    let unauthorized = Unauthorized {
+        www_authenticate,
+        received: Arc::default(),
+    };
+
+    let api = ClientBuilder::new(reqwest::Client::new())
+        .with(BearerAuthMiddleware::new(manager))
+        .with(unauthorized.clone())
+        .build();
+
+    (server, api, unauthorized)
+}
+
+#[tokio::test]
+async fn retry_once_on_invalid_token() {
+    let (server, api, unauthorized) =
+        setup(r#"Bearer realm="example", error="invalid_token""#).await;
+
+    let response = api.get("http://api.invalid/me").send().await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        *unauthorized.received.lock().unwrap(),
+        ["Bearer mock-access-token-1", "Bearer mock-access-token-2"]
+    );
+    assert_eq!(server.requests_to(Endpoint::Token).len(), 2);
+}
+
+#[tokio::test]
+async fn no_retry_without_invalid_token() {
+    let (server, api, unauthorized) = setup(r#"Bearer realm="example""#).await;
+
+    let response = api.get("http://api.invalid/me").send().await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        *unauthorized.received.lock().unwrap(),
+        ["Bearer mock-access-token-1"]
+    );
+    assert_eq!(server.requests_to(Endpoint::Token).len(), 1);
+}
+
+#[tokio::test]
+async fn quoted_commas() {
+    let (_server, api, unauthorized) =
+        setup(r#"Bearer error_description="a, b", error="invalid_token""#).await;
+
+    api.get("http://api.invalid/me").send().await.unwrap();
+    assert_eq!(unauthorized.received.lock().unwrap().len(), 2);
+
+    let (_server, api, unauthorized) =
+        setup(r#"Bearer error_description="a, error=invalid_token""#).await;
+
+    api.get("http://api.invalid/me").send().await.unwrap();
+    assert_eq!(unauthorized.received.lock().unwrap().len(), 1);
+}
@@ -0,0 +1,69 @@
+//! Tests for parsing and comparing scopes.
+
+use oauth2::{Scopes, StandardToken, Token};
+
+fn token(json: &str) -> StandardToken {
+    serde_json::from_str(json).unwrap()
+}
+
+#[test]
+fn parse_is_strict() {
+    let scopes = Scopes::parse("  read   write read ").unwrap();
+    assert_eq!(scopes.to_string(), "read write");
+
+    assert!(Scopes::parse("read\twrite").is_err());
+    assert!(Scopes::parse(r#"read "write""#).is_err());
+}
+
+#[test]
+fn deserialize_is_lenient() {
+    let token = token(
+        r#"{"access_token":"secret","token_type":"bearer","scope":"read\twrite  \"quoted\" résumé"}"#,
+    );
+
+    let scopes = token.scopes().unwrap();
+    assert_eq!(scopes.to_string(), r#"read write "quoted" résumé"#);
+
+    let token = self::token(
+        r#"{"access_token":"secret","token_type":"bearer","scope":["read write","admin\\all"]}"#,
+    );
+
+    let scopes = token.scopes().unwrap();
+    assert_eq!(scopes.to_string(), r"read write admin\all");
+}
+
+#[test]
+fn insert_is_not_validated() {
+    let mut scopes = Scopes::new();
+    assert!(scopes.insert("read write"));
+
+    assert_eq!(scopes.len(), 1);
+    assert_eq!(Scopes::parse(&scopes.to_string()).unwrap().len(), 2);
+}
+
+#[test]
+fn granted_scopes() {
+    let requested = Scopes::parse("read write").unwrap();
+
+    let omitted = token(r#"{"access_token":"secret","token_type":"bearer"}"#);
+    let granted = omitted.granted_scopes(&requested);
+    assert!(granted.is_identical());
+    assert_eq!(granted.scopes(), &requested);
+    assert_eq!(granted.granted(), &requested);
+
+    let narrowed = token(r#"{"access_token":"secret","token_type":"bearer","scope":"read"}"#);
+    let granted = narrowed.granted_scopes(&requested);
+    assert!(!granted.is_identical());
+    assert_eq!(granted.granted().to_string(), "read");
+    assert_eq!(granted.dropped().to_string(), "write");
+    assert!(granted.added().is_empty());
+
+    let added =
+        token(r#"{"access_token":"secret","token_type":"bearer","scope":"write read admin"}"#);
+    let granted = added.granted_scopes(&requested);
+    assert!(!granted.is_identical());
+    assert_eq!(granted.scopes().to_string(), "write read admin");
+    assert_eq!(granted.granted(), &requested);
+    assert!(granted.dropped().is_empty());
+    assert_eq!(granted.added().to_string(), "admin");
+}
@@ -0,0 +1,215 @@
+//! Tests for the requirements enforced by strict mode.
+
+use http::StatusCode;
+use oauth2::recording::{Recorder, Replay};
+use oauth2::{
+    Client, ErrorKind, PkceCodeVerifierS256, RefreshToken, StandardToken, State, StrictViolation,
+};
+use url::Url;
+
+const TOKEN: &str = r#"{"access_token":"access_token","token_type":"bearer"}"#;
+
+fn client(strict: bool) -> Client {
+    let mut client = Client::new(
+        "client_id",
+        Url::parse("http://authorize").unwrap(),
+        Url::parse("http://token").unwrap(),
+    );
+
+    client.set_client_secret("client_secret");
+    client.set_redirect_url(Url::parse("http://localhost/callback").unwrap());
+    client.set_strict(strict);
+    client
+}
+
+/// A recorder which replays a successful token response for each request.
+fn recorder() -> Recorder {
+    let recorder = Recorder::new();
+
+    for _ in 0..4 {
+        recorder.replay(Replay::json(StatusCode::OK, TOKEN));
+    }
+
+    recorder
+}
+
+/// Execute a request, returning the strict mode violation it was refused
+/// with if any.
+fn violation(recorder: &Recorder, request: oauth2::Request<'_>) -> Option<StrictViolation> {
+    match request.with_recorder(recorder).execute::<StandardToken>() {
+        Ok(..) => None,
+        Err(error) => {
+            assert_eq!(error.kind(), ErrorKind::Strict);
+            error.strict_violation()
+        }
+    }
+}
+
+#[test]
+fn implicit_grant() {
+    let state = State::from_random([42; 16]);
+
+    assert!(client(false).try_authorize_url_implicit(&state).is_ok());
+    assert_eq!(
+        client(true).try_authorize_url_implicit(&state),
+        Err(StrictViolation::ImplicitGrant)
+    );
+
+    // The unchecked variant is unaffected by strict mode.
+    let url = client(true).authorize_url_implicit(&state);
+    assert!(url
+        .query_pairs()
+        .any(|(key, value)| key == "response_type" && value == "token"));
+}
+
+#[test]
+fn authorize_pkce() {
+    let state = State::from_random([42; 16]);
+    let code_verifier = PkceCodeVerifierS256::from("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk");
+
+    assert!(client(false).authorize_request(&state).try_build().is_ok());
+
+    let client = client(true);
+
+    assert_eq!(
+        client.authorize_request(&state).try_build(),
+        Err(StrictViolation::MissingPkce)
+    );
+
+    assert_eq!(
+        client
+            .authorize_request(&state)
+            .param("code_challenge", "challenge")
+            .param("code_challenge_method", "plain")
+            .try_build(),
+        Err(StrictViolation::MissingPkce)
+    );
+
+    let url = client
+        .authorize_request(&state)
+        .pkce(&code_verifier)
+        .try_build()
+        .unwrap();
+
+    assert!(url
+        .query_pairs()
+        .any(|(key, value)| key == "code_challenge_method" && value == "S256"));
+}
+
+#[test]
+fn password_grant() {
+    let recorder = recorder();
+
+    let client = client(true);
+    let request = client.exchange_password("user", "pass");
+    assert_eq!(
+        violation(&recorder, request),
+        Some(StrictViolation::PasswordGrant)
+    );
+
+    // Refused requests are never sent.
+    assert!(recorder.recordings().is_empty());
+
+    let client = self::client(false);
+    assert_eq!(
+        violation(&recorder, client.exchange_password("user", "pass")),
+        None
+    );
+}
+
+#[test]
+fn missing_pkce() {
+    let recorder = recorder();
+    let client = client(true);
+
+    assert_eq!(
+        violation(&recorder, client.exchange_code("code")),
+        Some(StrictViolation::MissingPkce)
+    );
+
+    let request = client
+        .exchange_code("code")
+        .param("code_verifier", "verifier");
+
+    assert_eq!(violation(&recorder, request), None);
+    assert_eq!(recorder.recordings().len(), 1);
+}
+
+#[test]
+fn missing_redirect_url() {
+    let recorder = recorder();
+
+    let mut client = Client::new(
+        "client_id",
+        Url::parse("http://authorize").unwrap(),
+        Url::parse("http://token").unwrap(),
+    );
+
+    client.set_strict(true);
+
+    let request = client
+        .exchange_code("code")
+        .param("code_verifier", "verifier");
+
+    assert_eq!(
+        violation(&recorder, request),
+        Some(StrictViolation::MissingRedirectUrl)
+    );
+}
+
+#[test]
+fn redirect_url_fragment() {
+    let recorder = recorder();
+    let mut client = client(true);
+    client.set_redirect_url(Url::parse("http://localhost/callback#fragment").unwrap());
+
+    let request = client
+        .exchange_code("code")
+        .param("code_verifier", "verifier");
+
+    assert_eq!(
+        violation(&recorder, request),
+        Some(StrictViolation::RedirectUrlFragment)
+    );
+}
+
+#[test]
+fn bearer_token_in_query() {
+    let recorder = recorder();
+
+    let mut client = Client::new(
+        "client_id",
+        Url::parse("http://authorize").unwrap(),
+        Url::parse("http://token?access_token=secret").unwrap(),
+    );
+
+    assert_eq!(
+        violation(&recorder, client.exchange_client_credentials()),
+        None
+    );
+
+    client.set_strict(true);
+
+    assert_eq!(
+        violation(&recorder, client.exchange_client_credentials()),
+        Some(StrictViolation::BearerTokenInQuery)
+    );
+}
+
+#[test]
+fn other_grants_permitted() {
+    let recorder = recorder();
+    let client = client(true);
+
+    assert_eq!(
+        violation(&recorder, client.exchange_client_credentials()),
+        None
+    );
+
+    let refresh_token = RefreshToken::from("refresh_token");
+
+    assert_eq!(
+        violation(&recorder, client.exchange_refresh_token(&refresh_token)),
+        None
+    );
+}
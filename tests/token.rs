@@ -0,0 +1,92 @@
+//! Tests for parsing and serializing token responses.
+
+use std::time::Duration;
+
+use oauth2::{EmptyExtensions, StandardToken, Token, TokenType};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct OpenIdExtensions {
+    id_token: String,
+}
+
+#[test]
+fn custom_extensions() {
+    let token: StandardToken<OpenIdExtensions> = serde_json::from_str(
+        r#"{"access_token":"secret","token_type":"Bearer","id_token":"header.payload.signature","team":"T12345"}"#,
+    )
+    .unwrap();
+
+    assert_eq!(&**token.access_token(), "secret");
+    assert_eq!(token.extensions().id_token, "header.payload.signature");
+    assert!(!token.extra().contains_key("id_token"));
+    assert_eq!(token.extra()["team"], "T12345");
+
+    // Extension fields are required unless the extension type says otherwise.
+    assert!(serde_json::from_str::<StandardToken<OpenIdExtensions>>(
+        r#"{"access_token":"secret","token_type":"Bearer"}"#
+    )
+    .is_err());
+}
+
+#[test]
+fn empty_extensions() {
+    let token: StandardToken = serde_json::from_str(
+        r#"{"access_token":"secret","token_type":"bearer","expires_in":3600,"refresh_token":"refresh","scope":"read write","id_token":"header.payload.signature"}"#,
+    )
+    .unwrap();
+
+    assert_eq!(token.extensions(), &EmptyExtensions {});
+    assert_eq!(&**token.access_token(), "secret");
+    assert_eq!(token.expires_in(), Some(Duration::from_secs(3600)));
+    assert_eq!(token.refresh_token().map(|token| &**token), Some("refresh"));
+    assert_eq!(token.extra()["id_token"], "header.payload.signature");
+}
+
+#[test]
+fn extra_roundtrip() {
+    let json = r#"{"access_token":"secret","token_type":"bearer","team":{"id":"T12345","members":[1,2]},"quota":42.5,"limit":100}"#;
+
+    let token: StandardToken = serde_json::from_str(json).unwrap();
+    assert_eq!(token.extra()["team"]["members"][1], 2);
+    assert_eq!(token.extra()["quota"], 42.5);
+    assert_eq!(token.extra()["limit"], 100);
+
+    let serialized = serde_json::to_string(&token).unwrap();
+    let roundtrip: StandardToken = serde_json::from_str(&serialized).unwrap();
+    assert_eq!(roundtrip, token);
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&serialized).unwrap(),
+        serde_json::from_str::<serde_json::Value>(json).unwrap()
+    );
+}
+
+#[test]
+fn token_types() {
+    let token_type = |json: &str| serde_json::from_str::<TokenType>(json).unwrap();
+
+    assert_eq!(token_type(r#""Bearer""#), TokenType::Bearer);
+    assert_eq!(token_type(r#""bearer""#), TokenType::Bearer);
+    assert_eq!(token_type(r#""dpop""#), TokenType::DPoP);
+    assert_eq!(token_type(r#""n_a""#), TokenType::NA);
+
+    for json in [r#""DPoP""#, r#""N_A""#, r#""bearer""#, r#""mac""#] {
+        assert_eq!(serde_json::to_string(&token_type(json)).unwrap(), json);
+    }
+
+    let other = token_type(r#""PoP-Custom""#);
+    assert_eq!(other, TokenType::Other("PoP-Custom".to_owned()));
+    assert_eq!(serde_json::to_string(&other).unwrap(), r#""PoP-Custom""#);
+}
+
+#[test]
+fn authorization_schemes() {
+    assert_eq!(TokenType::Bearer.authorization_scheme(), Some("Bearer"));
+    assert_eq!(TokenType::DPoP.authorization_scheme(), Some("DPoP"));
+    assert_eq!(TokenType::Mac.authorization_scheme(), None);
+    assert_eq!(TokenType::NA.authorization_scheme(), None);
+    assert_eq!(
+        TokenType::Other("PoP-Custom".to_owned()).authorization_scheme(),
+        None
+    );
+}
@@ -0,0 +1,183 @@
+//! Tests for the tower integration against the mock authorization server.
+
+use std::error::Error as _;
+use std::sync::{Arc, Mutex};
+
+use http::header::AUTHORIZATION;
+use http::StatusCode;
+use oauth2::mock::{Endpoint, MockResponse, MockServer};
+use oauth2::tower::{BearerAuthLayer, TokenService};
+use oauth2::{BearerError, Client, ErrorKind, ExecuteError, StandardToken, Token, TokenManager};
+use serde_json::json;
+use tower::{Layer, Service, ServiceExt};
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+async fn setup() -> (MockServer, Client) {
+    let server = MockServer::start().await.unwrap();
+    server.register_client("client id", Some("client:secret"));
+
+    let mut client = server.client("client id");
+    client.set_client_secret("client:secret");
+    (server, client)
+}
+
+/// The `Authorization` headers received by a service.
+type Received = Arc<Mutex<Vec<String>>>;
+
+/// A service which responds with `status`, recording the `Authorization`
+/// header of every request it receives.
+fn recording_service(
+    status: StatusCode,
+    received: &Received,
+) -> impl Service<
+    http::Request<String>,
+    Response = http::Response<String>,
+    Error = BoxError,
+    Future = impl Send,
+> + Clone
+       + Send
+       + 'static {
+    let received = received.clone();
+
+    tower::service_fn(move |request: http::Request<String>| {
+        let received = received.clone();
+
+        async move {
+            let authorization = request.headers()[AUTHORIZATION].to_str()?.to_owned();
+            received.lock().unwrap().push(authorization);
+
+            let mut response = http::Response::new(String::new());
+            *response.status_mut() = status;
+            Ok::<_, BoxError>(response)
+        }
+    })
+}
+
+async fn call<S>(service: &mut S) -> http::Response<String>
+where
+    S: Service<http::Request<String>, Response = http::Response<String>, Error = BoxError>,
+{
+    service
+        .ready()
+        .await
+        .unwrap()
+        .call(http::Request::new(String::new()))
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn authorization_header() {
+    let (server, client) = setup().await;
+    let manager = TokenManager::new(client, reqwest::Client::new());
+
+    let received = Received::default();
+    let service = recording_service(StatusCode::OK, &received);
+    let mut service = BearerAuthLayer::new(manager).layer(service);
+
+    call(&mut service).await;
+    call(&mut service).await;
+
+    assert_eq!(
+        *received.lock().unwrap(),
+        ["Bearer mock-access-token-1", "Bearer mock-access-token-1"]
+    );
+    assert_eq!(server.requests_to(Endpoint::Token).len(), 1);
+}
+
+#[tokio::test]
+async fn refresh_on_expiry() {
+    let (server, client) = setup().await;
+
+    // Expires well within the default expiry skew.
+    server.enqueue(
+        Endpoint::Token,
+        MockResponse::json(
+            StatusCode::OK,
+            &json!({"access_token": "expiring", "token_type": "bearer", "expires_in": 1}),
+        ),
+    );
+
+    let manager = TokenManager::new(client, reqwest::Client::new());
+
+    let received = Received::default();
+    let service = recording_service(StatusCode::OK, &received);
+    let mut service = BearerAuthLayer::new(manager).layer(service);
+
+    call(&mut service).await;
+    call(&mut service).await;
+
+    assert_eq!(
+        *received.lock().unwrap(),
+        ["Bearer expiring", "Bearer mock-access-token-1"]
+    );
+    assert_eq!(server.requests_to(Endpoint::Token).len(), 2);
+}
+
+#[tokio::test]
+async fn retry_once_on_unauthorized() {
+    let (server, client) = setup().await;
+    let manager = TokenManager::new(client, reqwest::Client::new());
+
+    let received = Received::default();
+    let service = recording_service(StatusCode::UNAUTHORIZED, &received);
+    let mut service = BearerAuthLayer::new(manager).layer(service);
+
+    let response = call(&mut service).await;
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        *received.lock().unwrap(),
+        ["Bearer mock-access-token-1", "Bearer mock-access-token-2"]
+    );
+    assert_eq!(server.requests_to(Endpoint::Token).len(), 2);
+}
+
+#[tokio::test]
+async fn token_service() {
+    let (server, client) = setup().await;
+
+    let token = TokenService::<StandardToken>::new(reqwest::Client::new())
+        .oneshot(client.exchange_client_credentials())
+        .await
+        .unwrap();
+
+    assert_eq!(&**token.access_token(), "mock-access-token-1");
+
+    let requests = server.requests_to(Endpoint::Token);
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].form("grant_type"), Some("client_credentials"));
+}
+
+#[tokio::test]
+async fn unusable_token() {
+    let (server, client) = setup().await;
+
+    server.enqueue(
+        Endpoint::Token,
+        MockResponse::json(
+            StatusCode::OK,
+            &json!({"access_token": "not an access token", "token_type": "N_A"}),
+        ),
+    );
+
+    let manager = TokenManager::new(client, reqwest::Client::new());
+
+    let received = Received::default();
+    let service = recording_service(StatusCode::OK, &received);
+    let mut service = BearerAuthLayer::new(manager).layer(service);
+
+    let error = service
+        .ready()
+        .await
+        .unwrap()
+        .call(http::Request::new(String::new()))
+        .await
+        .unwrap_err();
+
+    let error = error.downcast::<ExecuteError>().unwrap();
+    assert_eq!(error.kind(), ErrorKind::BadResponse);
+    assert!(error.source().unwrap().is::<BearerError>());
+    assert!(received.lock().unwrap().is_empty());
+}
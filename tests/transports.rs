@@ -0,0 +1,642 @@
+//! Tests that the hyper and ureq transports send identical requests and
+//! classify errors the same way as the reqwest transport.
+
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use bytes::Bytes;
+use http::{HeaderMap, StatusCode};
+use http_body_util::{BodyExt, Full};
+use hyper::body::Incoming;
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper_util::client::legacy::connect::HttpConnector;
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use oauth2::{
+    BackchannelAuthenticationResponse, Client, ErrorField, ErrorKind, ExecuteError, RetryPolicy,
+    StandardToken, Token,
+};
+use tokio::net::TcpListener;
+use url::Url;
+
+type HyperClient = hyper_util::client::legacy::Client<HttpConnector, Full<Bytes>>;
+
+/// A request as received by the test server.
+#[derive(Debug, PartialEq)]
+struct Received {
+    method: http::Method,
+    path: String,
+    headers: Vec<(String, String)>,
+    body: Bytes,
+}
+
+/// A canned response served by the test server.
+#[derive(Clone)]
+struct Canned {
+    status: StatusCode,
+    content_type: Option<&'static str>,
+    body: &'static str,
+    retry_after: Option<&'static str>,
+    delay: Option<Duration>,
+}
+
+impl Canned {
+    fn json(status: StatusCode, body: &'static str) -> Self {
+        Self {
+            status,
+            content_type: Some("application/json"),
+            body,
+            retry_after: None,
+            delay: None,
+        }
+    }
+}
+
+/// Spawn a server which responds to every request with `canned`, recording
+/// the requests it receives.
+async fn serve(canned: Canned) -> (SocketAddr, Arc<Mutex<Vec<Received>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let received = Arc::new(Mutex::new(Vec::new()));
+
+    let log = received.clone();
+
+    tokio::spawn(async move {
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            let canned = canned.clone();
+            let log = log.clone();
+
+            let service = service_fn(move |request: http::Request<Incoming>| {
+                let canned = canned.clone();
+                let log = log.clone();
+
+                async move {
+                    let (parts, body) = request.into_parts();
+                    let body = body.collect().await.unwrap().to_bytes();
+
+                    log.lock().unwrap().push(Received {
+                        method: parts.method,
+                        path: parts.uri.path().to_owned(),
+                        headers: relevant_headers(&parts.headers),
+                        body,
+                    });
+
+                    if let Some(delay) = canned.delay {
+                        tokio::time::sleep(delay).await;
+                    }
+
+                    let mut response = http::Response::new(Full::new(Bytes::from(canned.body)));
+                    *response.status_mut() = canned.status;
+
+                    if let Some(content_type) = canned.content_type {
+                        response.headers_mut().insert(
+                            http::header::CONTENT_TYPE,
+                            http::HeaderValue::from_static(content_type),
+                        );
+                    }
+
+                    if let Some(retry_after) = canned.retry_after {
+                        response.headers_mut().insert(
+                            http::header::RETRY_AFTER,
+                            http::HeaderValue::from_static(retry_after),
+                        );
+                    }
+
+                    Ok::<_, Infallible>(response)
+                }
+            });
+
+            tokio::spawn(async move {
+                let _ = http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), service)
+                    .await;
+            });
+        }
+    });
+
+    (addr, received)
+}
+
+/// Headers which are set by the token request rather than by the transport.
+fn relevant_headers(headers: &HeaderMap) -> Vec<(String, String)> {
+    let mut relevant = headers
+        .iter()
+        .filter(|(name, _)| {
+            [
+                http::header::ACCEPT,
+                http::header::AUTHORIZATION,
+                http::header::CONTENT_TYPE,
+                http::header::CONTENT_LENGTH,
+            ]
+            .contains(name)
+        })
+        .map(|(name, value)| (name.to_string(), value.to_str().unwrap().to_owned()))
+        .collect::<Vec<_>>();
+
+    relevant.sort();
+    relevant
+}
+
+fn client(addr: SocketAddr) -> Client {
+    let mut client = Client::new(
+        "client id",
+        Url::parse(&format!("http://{addr}/authorize")).unwrap(),
+        Url::parse(&format!("http://{addr}/token")).unwrap(),
+    );
+
+    client.set_client_secret("client:secret");
+    client.set_redirect_url(Url::parse("http://localhost/callback").unwrap());
+    client.add_scope("read");
+    client
+}
+
+fn hyper_client() -> HyperClient {
+    hyper_util::client::legacy::Client::builder(TokioExecutor::new()).build_http()
+}
+
+/// Execute the same request through every transport.
+async fn execute_all(
+    client: &Client,
+    request: fn(&Client) -> oauth2::Request<'_>,
+) -> [Result<StandardToken, ExecuteError>; 3] {
+    let reqwest = request(client)
+        .with_reqwest_client(&reqwest::Client::new())
+        .execute()
+        .await;
+
+    let hyper = request(client)
+        .with_hyper_client(&hyper_client())
+        .execute()
+        .await;
+
+    let client = client.clone();
+
+    let ureq = tokio::task::spawn_blocking(move || {
+        request(&client)
+            .with_ureq_agent(&ureq::Agent::new_with_defaults())
+            .execute()
+    })
+    .await
+    .unwrap();
+
+    [reqwest, hyper, ureq]
+}
+
+const TOKEN: &str = r#"{"access_token":"token","token_type":"bearer","expires_in":3600}"#;
+
+#[tokio::test]
+async fn identical_requests() {
+    let (addr, received) = serve(Canned::json(StatusCode::OK, TOKEN)).await;
+    let client = client(addr);
+
+    let results = execute_all(&client, |client| {
+        client
+            .exchange_code("the code")
+            .param("code_verifier", "verifier")
+    })
+    .await;
+
+    for result in results {
+        assert_eq!(&**result.unwrap().access_token(), "token");
+    }
+
+    let results = execute_all(&client, |client| {
+        client.exchange_client_credentials().add_scope("write")
+    })
+    .await;
+
+    for result in results {
+        assert!(result.is_ok());
+    }
+
+    let received = received.lock().unwrap();
+    assert_eq!(received.len(), 6);
+    assert_eq!(received[0], received[1]);
+    assert_eq!(received[0], received[2]);
+    assert_eq!(received[3], received[4]);
+    assert_eq!(received[3], received[5]);
+
+    assert_eq!(received[0].method, http::Method::POST);
+    assert_eq!(received[0].path, "/token");
+    assert!(received[0].headers.contains(&(
+        "authorization".to_owned(),
+        "Basic Y2xpZW50K2lkOmNsaWVudCUzQXNlY3JldA==".to_owned()
+    )));
+    assert_eq!(
+        received[3].body,
+        "grant_type=client_credentials&scope=read+write&redirect_uri=http%3A%2F%2Flocalhost%2Fcallback"
+    );
+}
+
+#[tokio::test]
+async fn error_response() {
+    let (addr, _) = serve(Canned::json(
+        StatusCode::BAD_REQUEST,
+        r#"{"error":"invalid_grant"}"#,
+    ))
+    .await;
+
+    let client = client(addr);
+
+    let results = execute_all(&client, |client| client.exchange_code("code")).await;
+
+    for error in results.map(Result::unwrap_err) {
+        assert_eq!(error.kind(), ErrorKind::ErrorResponse);
+        assert_eq!(error.status(), Some(StatusCode::BAD_REQUEST));
+        assert!(error.is_invalid_grant());
+    }
+}
+
+#[tokio::test]
+async fn bad_and_empty_responses() {
+    let (addr, _) = serve(Canned {
+        status: StatusCode::BAD_GATEWAY,
+        content_type: Some("text/html"),
+        body: "<html>Bad Gateway</html>",
+        retry_after: None,
+        delay: None,
+    })
+    .await;
+
+    let client = client(addr);
+    let results = execute_all(&client, |client| client.exchange_code("code")).await;
+
+    for error in results.map(Result::unwrap_err) {
+        assert_eq!(error.kind(), ErrorKind::BadResponse);
+        assert_eq!(error.status(), Some(StatusCode::BAD_GATEWAY));
+        assert_eq!(error.body().unwrap(), "<html>Bad Gateway</html>");
+    }
+
+    let (addr, _) = serve(Canned::json(StatusCode::OK, "")).await;
+
+    let client = self::client(addr);
+    let results = execute_all(&client, |client| client.exchange_code("code")).await;
+
+    for error in results.map(Result::unwrap_err) {
+        assert_eq!(error.kind(), ErrorKind::EmptyResponse);
+    }
+}
+
+#[tokio::test]
+async fn transport_errors() {
+    // Bind and immediately drop a listener to find a port nothing listens on.
+    let addr = TcpListener::bind("127.0.0.1:0")
+        .await
+        .unwrap()
+        .local_addr()
+        .unwrap();
+
+    let client = client(addr);
+    let results = execute_all(&client, |client| client.exchange_code("code")).await;
+
+    for error in results.map(Result::unwrap_err) {
+        assert_eq!(error.kind(), ErrorKind::Transport);
+        assert_eq!(error.status(), None);
+        assert!(error.is_connect());
+    }
+}
+
+#[tokio::test]
+async fn timeouts() {
+    let (addr, _) = serve(Canned {
+        delay: Some(Duration::from_secs(5)),
+        ..Canned::json(StatusCode::OK, TOKEN)
+    })
+    .await;
+
+    let mut client = client(addr);
+    client.set_timeout(Duration::from_millis(100));
+
+    let results = execute_all(&client, |client| client.exchange_code("code")).await;
+
+    for error in results.map(Result::unwrap_err) {
+        assert_eq!(error.kind(), ErrorKind::Timeout);
+        assert!(error.is_timeout());
+    }
+}
+
+#[tokio::test]
+async fn form_urlencoded_responses() {
+    let (addr, _) = serve(Canned {
+        status: StatusCode::OK,
+        content_type: Some("application/x-www-form-urlencoded; charset=utf-8"),
+        body: "access_token=to%2Bken&token_type=bearer&expires_in=3600&scope=read+write",
+        retry_after: None,
+        delay: None,
+    })
+    .await;
+
+    let client = client(addr);
+    let results = execute_all(&client, |client| client.exchange_code("code")).await;
+
+    for token in results.map(Result::unwrap) {
+        assert_eq!(&**token.access_token(), "to+ken");
+        assert_eq!(token.expires_in(), Some(Duration::from_secs(3600)));
+        assert_eq!(token.scopes().unwrap().to_string(), "read write");
+    }
+
+    let (addr, _) = serve(Canned {
+        status: StatusCode::BAD_REQUEST,
+        content_type: Some("application/x-www-form-urlencoded"),
+        body: "error=invalid_grant&error_description=code+expired",
+        retry_after: None,
+        delay: None,
+    })
+    .await;
+
+    let client = self::client(addr);
+    let results = execute_all(&client, |client| client.exchange_code("code")).await;
+
+    for error in results.map(Result::unwrap_err) {
+        assert!(error.is_invalid_grant());
+        assert_eq!(
+            error.error_response().unwrap().error_description.as_deref(),
+            Some("code expired")
+        );
+    }
+}
+
+#[tokio::test]
+async fn text_plain_responses() {
+    for body in [
+        r#" {"access_token":"token","token_type":"bearer"}"#,
+        "access_token=token&token_type=bearer",
+    ] {
+        let (addr, _) = serve(Canned {
+            status: StatusCode::OK,
+            content_type: Some("text/plain"),
+            body,
+            retry_after: None,
+            delay: None,
+        })
+        .await;
+
+        let client = client(addr);
+        let results = execute_all(&client, |client| client.exchange_code("code")).await;
+
+        for token in results.map(Result::unwrap) {
+            assert_eq!(&**token.access_token(), "token");
+        }
+    }
+}
+
+#[tokio::test]
+async fn successful_error_responses() {
+    let (addr, _) = serve(Canned::json(
+        StatusCode::OK,
+        r#"{"error":"bad_verification_code","error_description":"The code is incorrect."}"#,
+    ))
+    .await;
+
+    let mut client = client(addr);
+    let results = execute_all(&client, |client| client.exchange_code("code")).await;
+
+    for error in results.map(Result::unwrap_err) {
+        assert_eq!(error.kind(), ErrorKind::BadResponse);
+        assert!(error.error_response().is_none());
+    }
+
+    client.set_detect_success_errors(true);
+    let results = execute_all(&client, |client| client.exchange_code("code")).await;
+
+    for error in results.map(Result::unwrap_err) {
+        assert_eq!(error.kind(), ErrorKind::ErrorResponse);
+        assert_eq!(error.status(), Some(StatusCode::OK));
+
+        let response = error.error_response().unwrap();
+        assert_eq!(
+            response.error,
+            ErrorField::Other("bad_verification_code".to_owned())
+        );
+        assert_eq!(
+            response.error_description.as_deref(),
+            Some("The code is incorrect.")
+        );
+    }
+
+    let (addr, _) = serve(Canned {
+        status: StatusCode::OK,
+        content_type: Some("application/x-www-form-urlencoded"),
+        body: "error=bad_verification_code",
+        retry_after: None,
+        delay: None,
+    })
+    .await;
+
+    let mut client = self::client(addr);
+    client.set_detect_success_errors(true);
+    let results = execute_all(&client, |client| client.exchange_code("code")).await;
+
+    for error in results.map(Result::unwrap_err) {
+        assert_eq!(error.kind(), ErrorKind::ErrorResponse);
+    }
+}
+
+/// Execute a request through reqwest with retries, returning the result
+/// along with the delays which were waited for between attempts.
+async fn execute_with_retry(
+    request: oauth2::Request<'_>,
+    policy: &RetryPolicy,
+) -> (Result<StandardToken, ExecuteError>, Vec<Duration>) {
+    let sleeps = Mutex::new(Vec::new());
+
+    let result = request
+        .with_reqwest_client(&reqwest::Client::new())
+        .execute_with_retry(policy, |delay| {
+            sleeps.lock().unwrap().push(delay);
+            async {}
+        })
+        .await;
+
+    (result, sleeps.into_inner().unwrap())
+}
+
+#[tokio::test]
+async fn retry_server_errors() {
+    let (addr, received) = serve(Canned::json(
+        StatusCode::SERVICE_UNAVAILABLE,
+        r#"{"error":"temporarily_unavailable"}"#,
+    ))
+    .await;
+
+    let client = client(addr);
+    let policy = RetryPolicy::new().max_retries(2);
+    let (result, sleeps) = execute_with_retry(client.exchange_code("code"), &policy).await;
+
+    assert_eq!(
+        result.unwrap_err().status(),
+        Some(StatusCode::SERVICE_UNAVAILABLE)
+    );
+    assert_eq!(sleeps.len(), 2);
+    assert!(sleeps[0] <= Duration::from_millis(100));
+    assert!(sleeps[1] <= Duration::from_millis(200));
+    assert_eq!(received.lock().unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn retry_after() {
+    let (addr, received) = serve(Canned {
+        retry_after: Some("1"),
+        ..Canned::json(StatusCode::TOO_MANY_REQUESTS, "")
+    })
+    .await;
+
+    let client = client(addr);
+    let policy = RetryPolicy::new().max_retries(1);
+    let (result, sleeps) = execute_with_retry(client.exchange_code("code"), &policy).await;
+
+    assert_eq!(
+        result.unwrap_err().status(),
+        Some(StatusCode::TOO_MANY_REQUESTS)
+    );
+    assert_eq!(sleeps, [Duration::from_secs(1)]);
+    assert_eq!(received.lock().unwrap().len(), 2);
+
+    // Delays beyond the maximum backoff and the unsupported HTTP-date form
+    // aren't retried early.
+    for retry_after in ["60", "Wed, 21 Oct 2015 07:28:00 GMT"] {
+        let (addr, received) = serve(Canned {
+            retry_after: Some(retry_after),
+            ..Canned::json(StatusCode::TOO_MANY_REQUESTS, "")
+        })
+        .await;
+
+        let client = self::client(addr);
+        let (result, sleeps) = execute_with_retry(client.exchange_code("code"), &policy).await;
+
+        assert_eq!(
+            result.unwrap_err().retry_after(),
+            retry_after.parse().ok().map(Duration::from_secs)
+        );
+        assert!(sleeps.is_empty());
+        assert_eq!(received.lock().unwrap().len(), 1);
+    }
+}
+
+#[tokio::test]
+async fn retry_invalid_grant() {
+    let (addr, received) = serve(Canned::json(
+        StatusCode::BAD_REQUEST,
+        r#"{"error":"invalid_grant"}"#,
+    ))
+    .await;
+
+    let client = client(addr);
+    let (result, sleeps) =
+        execute_with_retry(client.exchange_code("code"), &RetryPolicy::new()).await;
+
+    assert!(result.unwrap_err().is_invalid_grant());
+    assert!(sleeps.is_empty());
+    assert_eq!(received.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn retry_transport_errors() {
+    // Failures to connect are retried, since the request was never sent.
+    let addr = TcpListener::bind("127.0.0.1:0")
+        .await
+        .unwrap()
+        .local_addr()
+        .unwrap();
+
+    let client = client(addr);
+    let policy = RetryPolicy::new().max_retries(1);
+    let (result, sleeps) = execute_with_retry(client.exchange_code("code"), &policy).await;
+
+    assert!(result.unwrap_err().is_connect());
+    assert_eq!(sleeps.len(), 1);
+
+    // A timed out request might have been processed, so only idempotent
+    // requests are retried.
+    let (addr, received) = serve(Canned {
+        delay: Some(Duration::from_secs(5)),
+        ..Canned::json(StatusCode::OK, TOKEN)
+    })
+    .await;
+
+    let mut client = self::client(addr);
+    client.set_timeout(Duration::from_millis(100));
+
+    let (result, sleeps) = execute_with_retry(client.exchange_code("code"), &policy).await;
+    assert!(result.unwrap_err().is_timeout());
+    assert!(sleeps.is_empty());
+    assert_eq!(received.lock().unwrap().len(), 1);
+
+    let (result, sleeps) = execute_with_retry(client.exchange_client_credentials(), &policy).await;
+    assert!(result.unwrap_err().is_timeout());
+    assert_eq!(sleeps.len(), 1);
+    assert_eq!(received.lock().unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn cancelled_requests() {
+    let (addr, received) = serve(Canned {
+        delay: Some(Duration::from_millis(500)),
+        ..Canned::json(StatusCode::OK, TOKEN)
+    })
+    .await;
+
+    let client = client(addr);
+    let before = format!("{client:?}");
+
+    let request = client.exchange_code("code");
+    let reqwest_client = reqwest::Client::new();
+
+    // Drop the future once the server has received the request.
+    let cancelled = tokio::time::timeout(
+        Duration::from_millis(100),
+        request
+            .clone()
+            .with_reqwest_client(&reqwest_client)
+            .execute::<StandardToken>(),
+    )
+    .await;
+
+    assert!(cancelled.is_err());
+    assert_eq!(format!("{client:?}"), before);
+
+    // Both the client and the request can be used again.
+    let token = request
+        .with_reqwest_client(&reqwest_client)
+        .execute::<StandardToken>()
+        .await
+        .unwrap();
+
+    assert_eq!(&**token.access_token(), "token");
+
+    let received = received.lock().unwrap();
+    assert_eq!(received.len(), 2);
+    assert_eq!(received[0], received[1]);
+}
+
+#[tokio::test]
+async fn poll_backchannel_with_hyper() {
+    let (addr, received) = serve(Canned::json(StatusCode::OK, TOKEN)).await;
+    let client = client(addr);
+    let hyper = hyper_client();
+
+    let response = serde_json::from_str::<BackchannelAuthenticationResponse>(
+        r#"{"auth_req_id":"request id","expires_in":120}"#,
+    )
+    .unwrap();
+
+    let token = client
+        .poll_backchannel_authentication(
+            &response,
+            |request| request.with_hyper_client(&hyper).execute::<StandardToken>(),
+            |_| async {},
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(&**token.access_token(), "token");
+
+    let received = received.lock().unwrap();
+    assert_eq!(received.len(), 1);
+    assert!(received[0].body.starts_with(
+        b"grant_type=urn%3Aopenid%3Aparams%3Agrant-type%3Aciba&auth_req_id=request+id"
+    ));
+}